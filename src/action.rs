@@ -1,11 +1,18 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use serde::{
-  de::{self, Deserializer, Visitor},
+  de::{self, Deserializer, IntoDeserializer, Visitor},
   Deserialize, Serialize,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+use crate::{
+  app::Mode,
+  census::Census,
+  components::{graph::Sample, status::Status, universe::Tool},
+  parsers::Pattern,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Action {
   Tick,
   Render,
@@ -18,9 +25,151 @@ pub enum Action {
   Help,
   TogglePause,
   Insert(usize, usize),
+  Remove(usize, usize),
+  Toggle(usize, usize),
+  /// Flips the region of dead or live cells around a cell.
+  Fill(usize, usize),
+  UseTool(Tool),
+  CycleTool,
   UseHalfBlockUpper,
   UseHalfBlockLower,
   UseHalfBlockFull,
+  EnterMode(Mode),
+  OpenPicker,
+  LoadPattern(Pattern),
+  OpenLibrary,
+  StampPattern(Pattern),
+  Reset,
+  /// Fills the universe with the random soup of the current seed, instead of the starting pattern.
+  Reseed,
+  NextSeed,
+  PanLeft,
+  PanRight,
+  PanUp,
+  PanDown,
+  ZoomIn,
+  ZoomOut,
+  FitPattern,
+  ToggleFollow,
+  CycleRenderMode,
+  DumpFrame,
+  CycleTheme,
+  CycleOverlay,
+  Step,
+  StepN(usize),
+  RunUntil(usize),
+  PromptStepN,
+  PromptRunUntil,
+  /// Reports that the universe advanced by a number of generations.
+  Advanced(usize),
+  /// Reports the state of the universe for the status bar.
+  Status(Status),
+  /// Reports the population of the generations that were reached since the last frame.
+  Samples(Vec<Sample>),
+  ToggleGraph,
+  ExportPopulation,
+  TakeCensus,
+  /// Reports the objects that the universe, or the selection, is made of.
+  Census(Census),
+  Faster,
+  Slower,
+  /// Sets the speed in generations per second, pausing at 0.
+  SetSpeed(usize),
+  ToggleMaxSpeed,
+  TogglePauseOnPeriod,
+  Undo,
+  Redo,
+  StepBack,
+  /// Moves the edit cursor by a number of columns and rows.
+  MoveCursor(isize, isize),
+  ToggleCell,
+  StampClipboard,
+  /// Starts or stops selecting from the edit cursor.
+  ToggleMark,
+  SelectNone,
+  Copy,
+  Cut,
+  Paste,
+  ClearSelection,
+  /// Fills the selection with random cells, a percentage of which are alive.
+  FillSelection(usize),
+  InvertSelection,
+  /// Text that was pasted into the terminal, which is parsed as a pattern to paste.
+  PasteText(String),
+  CopyRle,
+  GrowEraser,
+  ShrinkEraser,
+}
+
+impl Action {
+  /// Describes what the action does, for the help popup.
+  pub fn description(&self) -> String {
+    match self {
+      Action::Quit => "Quit".into(),
+      Action::Suspend => "Suspend to the shell".into(),
+      Action::Help => "Show this help".into(),
+      Action::TogglePause => "Pause or resume".into(),
+      Action::UseHalfBlockUpper => "Draw on the upper half of characters".into(),
+      Action::UseHalfBlockLower => "Draw on the lower half of characters".into(),
+      Action::UseHalfBlockFull => "Draw on whole characters".into(),
+      Action::OpenPicker => "Choose a built-in pattern".into(),
+      Action::OpenLibrary => "Browse the pattern library".into(),
+      Action::Reset => "Start over".into(),
+      Action::Reseed => "Start over from the random soup of the current seed".into(),
+      Action::NextSeed => "Start over from the random soup of the next seed".into(),
+      Action::PanLeft => "Move the camera left".into(),
+      Action::PanRight => "Move the camera right".into(),
+      Action::PanUp => "Move the camera up".into(),
+      Action::PanDown => "Move the camera down".into(),
+      Action::ZoomIn => "Zoom in".into(),
+      Action::ZoomOut => "Zoom out".into(),
+      Action::FitPattern => "Zoom to fit all live cells".into(),
+      Action::ToggleFollow => "Follow the center of mass".into(),
+      Action::CycleRenderMode => "Switch to the next render mode".into(),
+      Action::DumpFrame => "Append the view to a text file".into(),
+      Action::CycleTheme => "Switch to the next color theme".into(),
+      Action::CycleOverlay => "Show trails or a heatmap".into(),
+      Action::Step => "Advance one generation and pause".into(),
+      Action::StepN(n) => format!("Advance {n} generations and pause"),
+      Action::RunUntil(generation) => format!("Run until generation {generation}"),
+      Action::PromptStepN => "Advance a number of generations and pause".into(),
+      Action::PromptRunUntil => "Run until a given generation".into(),
+      Action::Faster => "Run twice as fast".into(),
+      Action::Slower => "Run half as fast".into(),
+      Action::SetSpeed(0) => "Pause".into(),
+      Action::SetSpeed(speed) => format!("Run at {speed} generations per second"),
+      Action::ToggleMaxSpeed => "Run as fast as possible".into(),
+      Action::TogglePauseOnPeriod => "Pause when the universe or the selection starts repeating itself".into(),
+      Action::Undo => "Undo the last edit".into(),
+      Action::Redo => "Redo the last undone edit".into(),
+      Action::StepBack => "Go back one generation or edit and pause".into(),
+      Action::MoveCursor(0, dy) if *dy < 0 => format!("Move the cursor {} up", cells(-dy)),
+      Action::MoveCursor(0, dy) => format!("Move the cursor {} down", cells(*dy)),
+      Action::MoveCursor(dx, 0) if *dx < 0 => format!("Move the cursor {} left", cells(-dx)),
+      Action::MoveCursor(dx, 0) => format!("Move the cursor {} right", cells(*dx)),
+      Action::MoveCursor(dx, dy) => format!("Move the cursor by ({dx}, {dy})"),
+      Action::ToggleCell => "Toggle the cell under the cursor".into(),
+      Action::StampClipboard => "Stamp the clipboard at the cursor or the mouse".into(),
+      Action::ToggleMark => "Start or stop selecting from the cursor".into(),
+      Action::SelectNone => "Cancel the selection and pasting".into(),
+      Action::Copy => "Copy the selection to the clipboard".into(),
+      Action::Cut => "Cut the selection to the clipboard".into(),
+      Action::Paste => "Show the clipboard under the mouse or the cursor to paste it".into(),
+      Action::ClearSelection => "Kill the selected cells".into(),
+      Action::FillSelection(density) => format!("Fill the selection with {density}% live cells"),
+      Action::InvertSelection => "Invert the selected cells".into(),
+      Action::CopyRle => "Copy the selection to the system clipboard as RLE".into(),
+      Action::UseTool(tool) => format!("Draw with the {} tool", tool.name()),
+      Action::ToggleGraph => "Show or hide the population graph".into(),
+      Action::TakeCensus => "List the objects in the universe or the selection".into(),
+      Action::ExportPopulation => "Export the population history to a CSV file".into(),
+      Action::CycleTool => "Switch to the next drawing tool".into(),
+      Action::GrowEraser => "Make the eraser bigger".into(),
+      Action::ShrinkEraser => "Make the eraser smaller".into(),
+      Action::EnterMode(mode) => format!("Switch to {mode:?} mode"),
+      action => format!("{action:?}"),
+    }
+  }
 }
 
 impl<'de> Deserialize<'de> for Action {
@@ -41,28 +190,113 @@ impl<'de> Deserialize<'de> for Action {
       where
         E: de::Error,
       {
-        match value {
-          "Tick" => Ok(Action::Tick),
-          "Render" => Ok(Action::Render),
-          "Suspend" => Ok(Action::Suspend),
-          "Resume" => Ok(Action::Resume),
-          "Quit" => Ok(Action::Quit),
-          "Refresh" => Ok(Action::Refresh),
-          "Help" => Ok(Action::Help),
-          data if data.starts_with("Error(") => {
-            let error_msg = data.trim_start_matches("Error(").trim_end_matches(')');
-            Ok(Action::Error(error_msg.to_string()))
+        let (name, args) = match value.split_once('(') {
+          Some((name, args)) => {
+            let args = args.strip_suffix(')').ok_or_else(|| E::custom(format!("Missing `)` in {value}")))?;
+            (name.trim(), Some(args))
+          },
+          None => (value.trim(), None),
+        };
+        match (name, args) {
+          ("Tick", None) => Ok(Action::Tick),
+          ("Render", None) => Ok(Action::Render),
+          ("Suspend", None) => Ok(Action::Suspend),
+          ("Resume", None) => Ok(Action::Resume),
+          ("Quit", None) => Ok(Action::Quit),
+          ("Refresh", None) => Ok(Action::Refresh),
+          ("Help", None) => Ok(Action::Help),
+          ("TogglePause", None) => Ok(Action::TogglePause),
+          ("UseHalfBlockUpper", None) => Ok(Action::UseHalfBlockUpper),
+          ("UseHalfBlockLower", None) => Ok(Action::UseHalfBlockLower),
+          ("UseHalfBlockFull", None) => Ok(Action::UseHalfBlockFull),
+          ("OpenPicker", None) => Ok(Action::OpenPicker),
+          ("OpenLibrary", None) => Ok(Action::OpenLibrary),
+          ("Reset", None) => Ok(Action::Reset),
+          ("Reseed", None) => Ok(Action::Reseed),
+          ("NextSeed", None) => Ok(Action::NextSeed),
+          ("PanLeft", None) => Ok(Action::PanLeft),
+          ("PanRight", None) => Ok(Action::PanRight),
+          ("PanUp", None) => Ok(Action::PanUp),
+          ("PanDown", None) => Ok(Action::PanDown),
+          ("ZoomIn", None) => Ok(Action::ZoomIn),
+          ("ZoomOut", None) => Ok(Action::ZoomOut),
+          ("FitPattern", None) => Ok(Action::FitPattern),
+          ("ToggleFollow", None) => Ok(Action::ToggleFollow),
+          ("CycleRenderMode", None) => Ok(Action::CycleRenderMode),
+          ("DumpFrame", None) => Ok(Action::DumpFrame),
+          ("CycleTheme", None) => Ok(Action::CycleTheme),
+          ("CycleOverlay", None) => Ok(Action::CycleOverlay),
+          ("Step", None) => Ok(Action::Step),
+          ("PromptStepN", None) => Ok(Action::PromptStepN),
+          ("PromptRunUntil", None) => Ok(Action::PromptRunUntil),
+          ("Faster", None) => Ok(Action::Faster),
+          ("Slower", None) => Ok(Action::Slower),
+          ("ToggleMaxSpeed", None) => Ok(Action::ToggleMaxSpeed),
+          ("TogglePauseOnPeriod", None) => Ok(Action::TogglePauseOnPeriod),
+          ("Undo", None) => Ok(Action::Undo),
+          ("Redo", None) => Ok(Action::Redo),
+          ("StepBack", None) => Ok(Action::StepBack),
+          ("ToggleCell", None) => Ok(Action::ToggleCell),
+          ("StampClipboard", None) => Ok(Action::StampClipboard),
+          ("ToggleMark", None) => Ok(Action::ToggleMark),
+          ("SelectNone", None) => Ok(Action::SelectNone),
+          ("Copy", None) => Ok(Action::Copy),
+          ("Cut", None) => Ok(Action::Cut),
+          ("Paste", None) => Ok(Action::Paste),
+          ("ClearSelection", None) => Ok(Action::ClearSelection),
+          ("InvertSelection", None) => Ok(Action::InvertSelection),
+          ("CopyRle", None) => Ok(Action::CopyRle),
+          ("FillSelection", Some(density)) => {
+            let [density] = parse_args(density).map_err(E::custom)?;
+            Ok(Action::FillSelection(density))
+          },
+          ("MoveCursor", Some(args)) => {
+            let [dx, dy] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::MoveCursor(dx, dy))
           },
-          data if data.starts_with("Resize(") => {
-            let parts: Vec<&str> = data.trim_start_matches("Resize(").trim_end_matches(')').split(',').collect();
-            if parts.len() == 2 {
-              let width: u16 = parts[0].trim().parse().map_err(E::custom)?;
-              let height: u16 = parts[1].trim().parse().map_err(E::custom)?;
-              Ok(Action::Resize(width, height))
-            } else {
-              Err(E::custom(format!("Invalid Resize format: {}", value)))
-            }
+          ("CycleTool", None) => Ok(Action::CycleTool),
+          ("ToggleGraph", None) => Ok(Action::ToggleGraph),
+          ("ExportPopulation", None) => Ok(Action::ExportPopulation),
+          ("TakeCensus", None) => Ok(Action::TakeCensus),
+          ("UseTool", Some(tool)) => Ok(Action::UseTool(Tool::deserialize(tool.trim().into_deserializer())?)),
+          ("GrowEraser", None) => Ok(Action::GrowEraser),
+          ("ShrinkEraser", None) => Ok(Action::ShrinkEraser),
+          ("SetSpeed", Some(speed)) => {
+            let [speed] = parse_args(speed).map_err(E::custom)?;
+            Ok(Action::SetSpeed(speed))
           },
+          ("Step" | "StepN", Some(n)) => {
+            let [n] = parse_args(n).map_err(E::custom)?;
+            Ok(Action::StepN(n))
+          },
+          ("RunUntil", Some(generation)) => {
+            let [generation] = parse_args(generation).map_err(E::custom)?;
+            Ok(Action::RunUntil(generation))
+          },
+          ("Error", Some(message)) => Ok(Action::Error(message.to_string())),
+          ("Resize", Some(args)) => {
+            let [width, height] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::Resize(width, height))
+          },
+          ("Insert", Some(args)) => {
+            let [row, column] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::Insert(row, column))
+          },
+          ("Remove", Some(args)) => {
+            let [row, column] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::Remove(row, column))
+          },
+          ("Fill", Some(args)) => {
+            let [row, column] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::Fill(row, column))
+          },
+          ("Toggle", Some(args)) => {
+            let [row, column] = parse_args(args).map_err(E::custom)?;
+            Ok(Action::Toggle(row, column))
+          },
+          ("EnterMode", Some(mode)) => Ok(Action::EnterMode(Mode::deserialize(mode.trim().into_deserializer())?)),
+          ("LoadPattern", Some(spec)) => Ok(Action::LoadPattern(load_pattern(spec).map_err(E::custom)?)),
+          ("StampPattern", Some(spec)) => Ok(Action::StampPattern(load_pattern(spec).map_err(E::custom)?)),
           _ => Err(E::custom(format!("Unknown Action variant: {}", value))),
         }
       }
@@ -71,3 +305,69 @@ impl<'de> Deserialize<'de> for Action {
     deserializer.deserialize_str(ActionVisitor)
   }
 }
+
+/// Describes a distance of `n` cells, for the help popup.
+fn cells(n: isize) -> String {
+  if n == 1 {
+    "one cell".into()
+  } else {
+    format!("{n} cells")
+  }
+}
+
+/// Parses the comma separated arguments of a parameterized action such as `Resize(80, 24)`.
+fn parse_args<T: FromStr, const N: usize>(args: &str) -> Result<[T; N], String>
+where
+  T::Err: fmt::Display,
+{
+  let args: Vec<T> =
+    args.split(',').map(|arg| arg.trim().parse().map_err(|e| format!("{e}: `{arg}`"))).collect::<Result<_, _>>()?;
+  let count = args.len();
+  args.try_into().map_err(|_| format!("Expected {N} arguments, got {count}"))
+}
+
+/// Loads the pattern of a `LoadPattern` or `StampPattern` action, which can be a file, a built-in pattern or an
+/// apgcode.
+fn load_pattern(spec: &str) -> Result<Pattern, String> {
+  let spec = spec.trim().trim_matches(|c| c == '"' || c == '\'');
+  Pattern::load(spec).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn parse(value: &str) -> Result<Action, json5::Error> {
+    json5::from_str(&format!("{value:?}"))
+  }
+
+  #[test]
+  fn test_parameterized_actions() {
+    assert_eq!(parse("TogglePause").unwrap(), Action::TogglePause);
+    assert_eq!(parse("Resize(80, 24)").unwrap(), Action::Resize(80, 24));
+    assert_eq!(parse("Insert(3,4)").unwrap(), Action::Insert(3, 4));
+    assert_eq!(parse("Toggle(0, 7)").unwrap(), Action::Toggle(0, 7));
+    assert_eq!(parse("MoveCursor(-8, 0)").unwrap(), Action::MoveCursor(-8, 0));
+    assert_eq!(parse("UseTool(Ellipse)").unwrap(), Action::UseTool(Tool::Ellipse));
+    assert_eq!(parse("EnterMode(Library)").unwrap(), Action::EnterMode(Mode::Library));
+    assert_eq!(parse("Step").unwrap(), Action::Step);
+    assert_eq!(parse("Step(10)").unwrap(), Action::StepN(10));
+    assert_eq!(parse("StepN(10)").unwrap(), Action::StepN(10));
+    assert_eq!(parse("RunUntil(1000)").unwrap(), Action::RunUntil(1000));
+    assert_eq!(parse("SetSpeed(30)").unwrap(), Action::SetSpeed(30));
+    assert!(matches!(parse("StampPattern(glider)").unwrap(), Action::StampPattern(p) if p.cells.len() == 5));
+    assert!(matches!(parse("LoadPattern(xs4_33)").unwrap(), Action::LoadPattern(p) if p.cells.len() == 4));
+  }
+
+  #[test]
+  fn test_invalid_actions() {
+    assert!(parse("Resize(80)").is_err());
+    assert!(parse("Resize(80, x)").is_err());
+    assert!(parse("Insert(1, 2").is_err());
+    assert!(parse("TogglePause(1)").is_err());
+    assert!(parse("EnterMode(Nowhere)").is_err());
+    assert!(parse("Unknown").is_err());
+  }
+}
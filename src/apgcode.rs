@@ -0,0 +1,263 @@
+// Conversion between patterns and the apgcodes used by Catagolue to name objects, e.g. `xs4_33` (block), `xp2_7`
+// (blinker) or `xq4_153` (glider). See https://conwaylife.com/wiki/Apgcode
+use std::collections::HashSet;
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{
+  life::{self, Coord},
+  parsers::Pattern,
+};
+
+/// Longest period that is searched for when encoding a pattern.
+pub const MAX_PERIOD: usize = 1000;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Height of the horizontal strips that the extended Wechsler format encodes one column at a time.
+const STRIP_HEIGHT: isize = 5;
+
+/// The kind of object an apgcode describes, given by its prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefix {
+  /// `xs`, followed by the population.
+  StillLife(usize),
+  /// `xp`, followed by the period.
+  Oscillator(usize),
+  /// `xq`, followed by the period.
+  Spaceship(usize),
+}
+
+impl std::fmt::Display for Prefix {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Prefix::StillLife(population) => write!(f, "xs{population}"),
+      Prefix::Oscillator(period) => write!(f, "xp{period}"),
+      Prefix::Spaceship(period) => write!(f, "xq{period}"),
+    }
+  }
+}
+
+/// Returns true if `s` looks like an apgcode this module can decode.
+pub fn is_apgcode(s: &str) -> bool {
+  parse_prefix(s).is_ok()
+}
+
+/// Decodes an apgcode into a pattern in the phase and orientation given by the code.
+pub fn decode(code: &str) -> Result<Pattern> {
+  let (_, wechsler) = parse_prefix(code)?;
+  let cells = decode_wechsler(wechsler)?;
+  let area = life::bounding_box(&cells).map(|(x0, y0, x1, y1)| ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize));
+  Ok(Pattern { cells, name: Some(code.to_string()), area, ..Default::default() })
+}
+
+/// Encodes a still life, oscillator or spaceship as its canonical apgcode.
+///
+/// The canonical code is the shortest extended Wechsler string over every phase and orientation of the object, with
+/// ties broken by the lexicographically smallest string.
+pub fn encode(pattern: &Pattern) -> Result<String> {
+  let cells: HashSet<Coord> = pattern.cells.iter().copied().collect();
+  if cells.is_empty() {
+    return Err(eyre!("Cannot encode an empty pattern as an apgcode"));
+  }
+  let periodicity = life::find_period(&cells, MAX_PERIOD)
+    .ok_or_else(|| eyre!("Pattern is not a still life, oscillator or spaceship with period at most {}", MAX_PERIOD))?;
+  let prefix = match periodicity {
+    life::Periodicity { period: 1, displacement: (0, 0) } => Prefix::StillLife(cells.len()),
+    life::Periodicity { period, displacement: (0, 0) } => Prefix::Oscillator(period),
+    life::Periodicity { period, .. } => Prefix::Spaceship(period),
+  };
+
+  let mut best: Option<String> = None;
+  let mut phase = cells;
+  for _ in 0..periodicity.period {
+    for transform in 0..8 {
+      let transformed: Vec<Coord> = phase.iter().map(|&c| orient(c, transform)).collect();
+      let candidate = encode_wechsler(&transformed);
+      let shorter = |b: &String| (candidate.len(), &candidate) < (b.len(), b);
+      if best.as_ref().is_none_or(shorter) {
+        best = Some(candidate);
+      }
+    }
+    phase = life::step(&phase);
+  }
+  Ok(format!("{}_{}", prefix, best.unwrap_or_default()))
+}
+
+/// Applies one of the eight rotations and reflections of the square to a coordinate.
+fn orient((x, y): Coord, transform: u8) -> Coord {
+  match transform {
+    0 => (x, y),
+    1 => (-x, y),
+    2 => (x, -y),
+    3 => (-x, -y),
+    4 => (y, x),
+    5 => (-y, x),
+    6 => (y, -x),
+    _ => (-y, -x),
+  }
+}
+
+/// Returns the kind of object an apgcode describes.
+pub fn prefix(code: &str) -> Result<Prefix> {
+  parse_prefix(code).map(|(prefix, _)| prefix)
+}
+
+fn parse_prefix(code: &str) -> Result<(Prefix, &str)> {
+  let (prefix, wechsler) = code.split_once('_').ok_or_else(|| eyre!("Missing `_` in apgcode `{}`", code))?;
+  let number = |s: &str| s.parse::<usize>().map_err(|e| eyre!("Invalid number in apgcode prefix `{}`: {}", prefix, e));
+  let prefix = if let Some(n) = prefix.strip_prefix("xs") {
+    Prefix::StillLife(number(n)?)
+  } else if let Some(n) = prefix.strip_prefix("xp") {
+    Prefix::Oscillator(number(n)?)
+  } else if let Some(n) = prefix.strip_prefix("xq") {
+    Prefix::Spaceship(number(n)?)
+  } else {
+    return Err(eyre!("Unsupported apgcode prefix `{}`, expected one of `xs`, `xp` or `xq`", prefix));
+  };
+  if wechsler.is_empty() || !wechsler.bytes().all(|b| DIGITS.contains(&b)) {
+    return Err(eyre!("Invalid extended Wechsler string `{}`", wechsler));
+  }
+  Ok((prefix, wechsler))
+}
+
+/// Encodes cells in the extended Wechsler format, relative to their bounding box.
+pub fn encode_wechsler(cells: &[Coord]) -> String {
+  let (_, cells) = life::normalize(cells);
+  let (_, _, max_x, max_y) = life::bounding_box(&cells).unwrap_or_default();
+  let cells: HashSet<Coord> = cells.into_iter().collect();
+
+  let mut s = String::new();
+  for strip in 0..=max_y / STRIP_HEIGHT {
+    if strip > 0 {
+      s.push('z');
+    }
+    let mut zeros = 0;
+    for x in 0..=max_x {
+      let value = (0..STRIP_HEIGHT)
+        .filter(|bit| cells.contains(&(x, strip * STRIP_HEIGHT + bit)))
+        .fold(0, |value, bit| value | 1 << bit);
+      if value == 0 {
+        zeros += 1;
+      } else {
+        push_zeros(&mut s, zeros);
+        zeros = 0;
+        s.push(DIGITS[value] as char);
+      }
+    }
+  }
+  s
+}
+
+fn push_zeros(s: &mut String, mut zeros: usize) {
+  while zeros > 39 {
+    s.push_str("yz");
+    zeros -= 39;
+  }
+  match zeros {
+    0 => {},
+    1 => s.push('0'),
+    2 => s.push('w'),
+    3 => s.push('x'),
+    n => {
+      s.push('y');
+      s.push(DIGITS[n - 4] as char);
+    },
+  }
+}
+
+/// Decodes an extended Wechsler string into cells whose bounding box starts at the origin.
+pub fn decode_wechsler(s: &str) -> Result<Vec<Coord>> {
+  let mut cells = vec![];
+  let (mut x, mut strip) = (0, 0);
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      'w' => x += 2,
+      'x' => x += 3,
+      'y' => {
+        let n = chars.next().ok_or_else(|| eyre!("Expected a character after `y` in `{}`", s))?;
+        let n = n.to_digit(36).ok_or_else(|| eyre!("Unexpected character `{}` after `y` in `{}`", n, s))?;
+        x += 4 + n as isize;
+      },
+      'z' => {
+        x = 0;
+        strip += 1;
+      },
+      c => {
+        let value = c.to_digit(32).ok_or_else(|| eyre!("Unexpected character `{}` in `{}`", c, s))?;
+        for bit in 0..STRIP_HEIGHT {
+          if value & (1 << bit) != 0 {
+            cells.push((x, strip * STRIP_HEIGHT + bit));
+          }
+        }
+        x += 1;
+      },
+    }
+  }
+  Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::parsers::parse_rle_file;
+
+  #[test]
+  fn test_decode_glider() -> Result<()> {
+    let pattern = decode("xq4_153")?;
+    let mut cells = pattern.cells;
+    cells.sort();
+    assert_eq!(cells, vec![(0, 0), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_encode_still_lifes() -> Result<()> {
+    assert_eq!(encode(&parse_rle_file("x = 2, y = 2\n2o$2o!")?)?, "xs4_33");
+    assert_eq!(encode(&parse_rle_file("x = 4, y = 3\nb2ob$o2bo$b2o!")?)?, "xs6_696");
+    Ok(())
+  }
+
+  #[test]
+  fn test_encode_oscillators() -> Result<()> {
+    assert_eq!(encode(&parse_rle_file("x = 3, y = 1\n3o!")?)?, "xp2_7");
+    Ok(())
+  }
+
+  #[test]
+  fn test_encode_spaceships() -> Result<()> {
+    assert_eq!(encode(&parse_rle_file("x = 3, y = 3\nbob$2bo$3o!")?)?, "xq4_153");
+    assert_eq!(encode(&parse_rle_file("x = 5, y = 4\nbo2bo$o4b$o3bo$4o!")?)?, "xq4_6frc");
+    Ok(())
+  }
+
+  #[test]
+  fn test_round_trip() -> Result<()> {
+    for code in
+      ["xs4_33", "xs6_696", "xp2_7", "xq4_153", "xq4_6frc", "xp15_4r4z4r4", "xs7_178c", "xs5_253", "xp2_7e", "xp2_318c"]
+    {
+      assert_eq!(encode(&decode(code)?)?, code);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_wechsler_zeros() -> Result<()> {
+    let cells = vec![(0, 0), (45, 0)];
+    let s = encode_wechsler(&cells);
+    assert_eq!(s, "1yzy11");
+    assert_eq!(decode_wechsler(&s)?, cells);
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_codes() {
+    assert!(decode("xz4_153").is_err());
+    assert!(decode("xq4").is_err());
+    assert!(decode("xq4_15!").is_err());
+    assert!(!is_apgcode("glider.rle"));
+  }
+}
@@ -1,5 +1,3 @@
-use std::path::PathBuf;
-
 use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::prelude::Rect;
@@ -8,15 +6,26 @@ use tokio::sync::mpsc;
 
 use crate::{
   action::Action,
-  components::{fps::FpsCounter, universe::Universe, Component},
+  components::{
+    census::CensusTable, fps::FpsCounter, graph::Graph, help::Help, library::Library, picker::Picker, prompt::Prompt,
+    status::StatusBar, universe::Universe, Component,
+  },
   config::Config,
+  parsers::Pattern,
   tui,
 };
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Mode {
   #[default]
   Home,
+  /// Editing cells with a keyboard cursor.
+  Edit,
+  Picker,
+  Library,
+  Help,
+  Prompt,
+  Census,
 }
 
 pub struct App {
@@ -28,25 +37,41 @@ pub struct App {
   pub should_suspend: bool,
   pub mode: Mode,
   pub last_tick_key_events: Vec<KeyEvent>,
-  pub filename: Option<PathBuf>,
 }
 
 impl App {
-  pub fn new(tick_rate: f64, frame_rate: f64, filename: Option<PathBuf>) -> Result<Self> {
-    let universe = Universe::new(filename.clone());
+  pub fn new(tick_rate: f64, frame_rate: f64, pattern: Option<Pattern>) -> Result<Self> {
+    let universe = Universe::new(pattern, tick_rate);
     let fps = FpsCounter::default();
+    let picker = Picker::new();
+    let library = Library::new();
+    let prompt = Prompt::new();
+    let status = StatusBar::new();
+    let graph = Graph::new();
+    let census = CensusTable::new();
     let config = Config::new()?;
     let mode = Mode::Home;
+    let mut components: Vec<Box<dyn Component>> = vec![
+      Box::new(universe),
+      Box::new(fps),
+      Box::new(status),
+      Box::new(graph),
+      Box::new(picker),
+      Box::new(library),
+      Box::new(prompt),
+      Box::new(census),
+    ];
+    let help = Help::new(components.iter().flat_map(|component| component.bindings()).collect());
+    components.push(Box::new(help));
     Ok(Self {
       tick_rate,
       frame_rate,
-      components: vec![Box::new(universe), Box::new(fps)],
+      components,
       should_quit: false,
       should_suspend: false,
       config,
       mode,
       last_tick_key_events: Vec::new(),
-      filename,
     })
   }
 
@@ -78,6 +103,7 @@ impl App {
           tui::Event::Tick => action_tx.send(Action::Tick)?,
           tui::Event::Render => action_tx.send(Action::Render)?,
           tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+          tui::Event::Paste(ref text) => action_tx.send(Action::PasteText(text.clone()))?,
           tui::Event::Key(key) => {
             if let Some(keymap) = self.config.keybindings.get(&self.mode) {
               if let Some(action) = keymap.get(&vec![key]) {
@@ -106,7 +132,10 @@ impl App {
       }
 
       while let Ok(action) = action_rx.try_recv() {
-        if action != Action::Tick && action != Action::Render {
+        if !matches!(
+          action,
+          Action::Tick | Action::Render | Action::Advanced(_) | Action::Status(_) | Action::Samples(_)
+        ) {
           log::debug!("{action:?}");
         }
         match action {
@@ -116,6 +145,7 @@ impl App {
           Action::Quit => self.should_quit = true,
           Action::Suspend => self.should_suspend = true,
           Action::Resume => self.should_suspend = false,
+          Action::EnterMode(mode) => self.mode = mode,
           Action::Resize(w, h) => {
             tui.resize(Rect::new(0, 0, w, h))?;
             tui.draw(|f| {
@@ -0,0 +1,62 @@
+// Patterns that are embedded in the binary so that they are available without any files on disk.
+use color_eyre::eyre::{eyre, Result};
+
+use crate::parsers::{parse_rle_file, Pattern};
+
+/// Built-in patterns as `(name, rle)` pairs, in the order they are listed in the picker.
+pub const CATALOG: &[(&str, &str)] = &[
+  ("glider", include_str!("../patterns/glider.rle")),
+  ("lwss", include_str!("../patterns/lwss.rle")),
+  ("mwss", include_str!("../patterns/mwss.rle")),
+  ("hwss", include_str!("../patterns/hwss.rle")),
+  ("gosper-glider-gun", include_str!("../patterns/gosper-glider-gun.rle")),
+  ("pulsar", include_str!("../patterns/pulsar.rle")),
+  ("pentadecathlon", include_str!("../patterns/pentadecathlon.rle")),
+  ("acorn", include_str!("../patterns/acorn.rle")),
+  ("r-pentomino", include_str!("../patterns/r-pentomino.rle")),
+  ("diehard", include_str!("../patterns/diehard.rle")),
+  ("puffer-train", include_str!("../patterns/puffer-train.rle")),
+  ("space-rake", include_str!("../patterns/space-rake.rle")),
+];
+
+pub fn names() -> impl Iterator<Item = &'static str> {
+  CATALOG.iter().map(|(name, _)| *name)
+}
+
+/// Parses the built-in pattern with the given name.
+pub fn get(name: &str) -> Result<Pattern> {
+  let (_, rle) = CATALOG.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).ok_or_else(|| {
+    eyre!("Unknown built-in pattern `{}`, expected one of: {}", name, names().collect::<Vec<_>>().join(", "))
+  })?;
+  parse_rle_file(rle)
+}
+
+/// Parses every built-in pattern.
+pub fn patterns() -> Result<Vec<(&'static str, Pattern)>> {
+  CATALOG.iter().map(|(name, rle)| Ok((*name, parse_rle_file(rle)?))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_catalog_parses() -> Result<()> {
+    for (name, pattern) in patterns()? {
+      assert!(pattern.name.is_some(), "{name} has no name");
+      assert!(!pattern.cells.is_empty(), "{name} has no cells");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_catalog_objects() -> Result<()> {
+    assert_eq!(get("glider")?.apgcode()?, "xq4_153");
+    assert_eq!(get("lwss")?.apgcode()?, "xq4_6frc");
+    assert_eq!(get("pentadecathlon")?.apgcode()?, "xp15_4r4z4r4");
+    assert!(get("unknown").is_err());
+    Ok(())
+  }
+}
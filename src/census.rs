@@ -0,0 +1,200 @@
+// Splitting live cells into separate objects and naming them, like the census that Catagolue takes of soups.
+use std::collections::{HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::{
+  apgcode::{self, Prefix},
+  life::Coord,
+  parsers::Pattern,
+};
+
+/// Names of common objects, by apgcode.
+pub const NAMES: &[(&str, &str)] = &[
+  ("xs4_33", "block"),
+  ("xs6_696", "beehive"),
+  ("xs7_2596", "loaf"),
+  ("xs5_253", "boat"),
+  ("xs6_356", "ship"),
+  ("xs4_252", "tub"),
+  ("xs8_6996", "pond"),
+  ("xs7_25ac", "long boat"),
+  ("xs6_25a4", "barge"),
+  ("xs7_178c", "eater 1"),
+  ("xp2_7", "blinker"),
+  ("xp2_7e", "toad"),
+  ("xp2_318c", "beacon"),
+  ("xp15_4r4z4r4", "pentadecathlon"),
+  ("xq4_153", "glider"),
+  ("xq4_6frc", "lightweight spaceship"),
+];
+
+pub fn name(apgcode: &str) -> Option<&'static str> {
+  NAMES.iter().find(|(code, _)| *code == apgcode).map(|(_, name)| *name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+  StillLife,
+  Oscillator,
+  Spaceship,
+  /// Did not repeat itself on its own within the longest period that apgcodes are searched for.
+  Unclassified,
+}
+
+/// An object and how many times it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Object {
+  pub apgcode: Option<String>,
+  pub name: Option<String>,
+  pub kind: Kind,
+  pub period: Option<usize>,
+  /// Number of live cells in the first island that the object was found in.
+  pub population: usize,
+  pub count: usize,
+}
+
+impl Object {
+  /// Returns whether two objects are counted together, which unclassified ones are when they have the same population.
+  pub fn is_same(&self, other: &Object) -> bool {
+    self.apgcode == other.apgcode && (self.apgcode.is_some() || self.population == other.population)
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Census {
+  pub population: usize,
+  /// Objects from the most to the least common. Unclassified islands are counted together by population.
+  pub objects: Vec<Object>,
+}
+
+impl Census {
+  /// Takes a census of the live cells, which wrap around the edges when the size of a torus is given.
+  pub fn take(cells: &[Coord], distance: usize, torus: Option<(usize, usize)>) -> Self {
+    let mut objects: Vec<Object> = vec![];
+    for island in islands(cells, distance, torus) {
+      let object = classify(&island);
+      match objects.iter_mut().find(|o| o.is_same(&object)) {
+        Some(o) => o.count += 1,
+        None => objects.push(object),
+      }
+    }
+    objects.sort_by(|a, b| b.count.cmp(&a.count).then(a.kind.cmp(&b.kind)).then(a.apgcode.cmp(&b.apgcode)));
+    Self { population: cells.len(), objects }
+  }
+
+  /// Returns the number of objects that were found.
+  pub fn count(&self) -> usize {
+    self.objects.iter().map(|o| o.count).sum()
+  }
+}
+
+/// Splits live cells into islands of cells that can be reached from each other in steps of at most `distance` cells
+/// horizontally and vertically.
+///
+/// On a torus, islands that cross an edge are returned in one piece, with coordinates outside of the torus.
+pub fn islands(cells: &[Coord], distance: usize, torus: Option<(usize, usize)>) -> Vec<Vec<Coord>> {
+  let wrap = |(x, y): Coord| {
+    match torus {
+      Some((width, height)) => (x.rem_euclid(width as isize), y.rem_euclid(height as isize)),
+      None => (x, y),
+    }
+  };
+  let d = distance as isize;
+  let mut unvisited: HashSet<Coord> = cells.iter().map(|&c| wrap(c)).collect();
+  let mut islands = vec![];
+  for &start in cells {
+    let start = wrap(start);
+    if !unvisited.remove(&start) {
+      continue;
+    }
+    let mut island = vec![start];
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+      for dy in -d..=d {
+        for dx in -d..=d {
+          let neighbor = (x + dx, y + dy);
+          if unvisited.remove(&wrap(neighbor)) {
+            island.push(neighbor);
+            queue.push_back(neighbor);
+          }
+        }
+      }
+    }
+    islands.push(island);
+  }
+  islands
+}
+
+/// Runs an island on its own to find its apgcode, kind and period.
+fn classify(island: &[Coord]) -> Object {
+  let pattern = Pattern { cells: island.to_vec(), ..Default::default() };
+  let code = apgcode::encode(&pattern).ok();
+  let (kind, period) = match code.as_deref().map(apgcode::prefix) {
+    Some(Ok(Prefix::StillLife(_))) => (Kind::StillLife, Some(1)),
+    Some(Ok(Prefix::Oscillator(period))) => (Kind::Oscillator, Some(period)),
+    Some(Ok(Prefix::Spaceship(period))) => (Kind::Spaceship, Some(period)),
+    _ => (Kind::Unclassified, None),
+  };
+  Object {
+    name: code.as_deref().and_then(name).map(String::from),
+    apgcode: code,
+    kind,
+    period,
+    population: island.len(),
+    count: 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use color_eyre::eyre::Result;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::parsers::parse_rle_file;
+
+  #[test]
+  fn test_names() -> Result<()> {
+    for (rle, name) in [
+      ("x = 3, y = 3\nbo$obo$bo!", "tub"),
+      ("x = 3, y = 3\n2o$obo$bo!", "boat"),
+      ("x = 3, y = 3\n2o$obo$b2o!", "ship"),
+      ("x = 4, y = 4\nb2o$o2bo$bobo$2bo!", "loaf"),
+      ("x = 4, y = 4\nb2o$o2bo$o2bo$b2o!", "pond"),
+      ("x = 4, y = 4\n2o$obo$2bo$2b2o!", "eater 1"),
+      ("x = 4, y = 2\nb3o$3o!", "toad"),
+      ("x = 4, y = 4\n2o$2o$2b2o$2b2o!", "beacon"),
+    ] {
+      assert_eq!(self::name(&parse_rle_file(rle)?.apgcode()?), Some(name));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_take() {
+    let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    let blinker = [(10, 0), (11, 0), (12, 0)];
+    let glider = [(21, 0), (22, 1), (20, 2), (21, 2), (22, 2)];
+    let cells = [&block[..], &blinker, &glider, &block.map(|(x, y)| (x, y + 10))].concat();
+    let census = Census::take(&cells, 2, None);
+    assert_eq!(census.count(), 4);
+    let names: Vec<_> = census.objects.iter().map(|o| (o.name.as_deref(), o.count)).collect();
+    assert_eq!(names, vec![(Some("block"), 2), (Some("blinker"), 1), (Some("glider"), 1)]);
+    assert_eq!(census.objects[1].kind, Kind::Oscillator);
+    assert_eq!(census.objects[1].period, Some(2));
+  }
+
+  #[test]
+  fn test_islands() {
+    // A block split by the edges of the torus is one island, but four separate cells on the plane.
+    let cells = [(0, 0), (7, 0), (0, 7), (7, 7)];
+    assert_eq!(islands(&cells, 1, Some((8, 8))).len(), 1);
+    assert_eq!(islands(&cells, 1, None).len(), 4);
+    // The cells of a lightweight spaceship are up to two cells apart.
+    let lwss = [(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)];
+    assert_eq!(islands(&lwss, 1, None).len(), 2);
+    assert_eq!(islands(&lwss, 2, None).len(), 1);
+  }
+}
@@ -1,8 +1,20 @@
-use std::path::PathBuf;
+use std::{collections::HashSet, io::Write, path::PathBuf, time::Instant};
 
-use clap::Parser;
+use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
 
-use crate::utils::version;
+use crate::{
+  catalog,
+  census::Census,
+  components::universe::{ColorSupport, RenderMode},
+  config::Config,
+  life::{self, Coord},
+  parsers::Pattern,
+  search::Search,
+  soup::Symmetry,
+  utils::{get_data_dir, version},
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -13,6 +25,196 @@ pub struct Cli {
   #[arg(long, value_name = "FLOAT", help = "Frame rate, i.e. number of frames per second", default_value_t = 60.0)]
   pub frame_rate: f64,
 
-  #[arg(short, long, help = "Path to pattern file")]
+  #[command(subcommand)]
+  pub command: Option<Command>,
+
+  #[arg(
+    short,
+    long,
+    global = true,
+    help = "Path to pattern file, name of a built-in pattern, or an apgcode such as xq4_153"
+  )]
   pub pattern: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "CODE",
+    global = true,
+    conflicts_with = "pattern",
+    help = "Start from an object given by its apgcode"
+  )]
+  pub apgcode: Option<String>,
+
+  #[arg(
+    long,
+    value_name = "NAME",
+    global = true,
+    conflicts_with_all = ["pattern", "apgcode"],
+    value_parser = PossibleValuesParser::new(catalog::names()),
+    help = "Start from a built-in pattern"
+  )]
+  pub catalog: Option<String>,
+
+  #[arg(long, value_name = "SEED", help = "Seed of the random soup that fills the universe when there is no pattern")]
+  pub seed: Option<u64>,
+
+  #[arg(long, value_name = "FLOAT", value_parser = parse_density, help = "Fraction of cells that are alive in the random soup [default: 0.5]")]
+  pub density: Option<f64>,
+
+  #[arg(long, value_name = "WxH", value_parser = parse_size, help = "Size of the random soup, instead of the whole universe")]
+  pub soup_size: Option<(usize, usize)>,
+
+  #[arg(long, value_enum, ignore_case = true, help = "Symmetry of the random soup [default: C1]")]
+  pub symmetry: Option<Symmetry>,
+
+  #[arg(long, value_enum, help = "How to draw cells, instead of detecting it from TERM")]
+  pub render_mode: Option<RenderMode>,
+
+  #[arg(long, value_enum, help = "Which colors to use, instead of detecting them from COLORTERM and TERM")]
+  pub colors: Option<ColorSupport>,
+}
+
+/// Commands that run without the TUI.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Print the objects that the pattern is made of as JSON.
+  Census {
+    #[arg(long, value_name = "N", help = "Number of generations to run the pattern for first", default_value_t = 0)]
+    generations: usize,
+
+    #[arg(
+      long,
+      value_name = "CELLS",
+      help = "How many cells apart the cells of one object can be [default: from the config]"
+    )]
+    distance: Option<usize>,
+  },
+  /// Search random 16x16 soups for the objects they produce and print the counts as JSON.
+  Search {
+    #[arg(long, value_name = "N", help = "Number of soups to search", default_value_t = 1000)]
+    soups: usize,
+
+    #[arg(
+      long,
+      value_name = "SEED",
+      help = "Seed of the first soup, which the other seeds count up from",
+      default_value_t = 0
+    )]
+    seed: u64,
+
+    #[arg(long, value_name = "N", help = "Number of threads to search with [default: one per core]")]
+    threads: Option<usize>,
+
+    #[arg(
+      long,
+      value_name = "CELLS",
+      help = "How many cells apart the cells of one object can be [default: from the config]"
+    )]
+    distance: Option<usize>,
+
+    #[arg(
+      long,
+      value_name = "N",
+      help = "Flag objects that are found in at most this many soups as rare",
+      default_value_t = 1
+    )]
+    rare: usize,
+
+    #[arg(long, value_enum, ignore_case = true, help = "Symmetry of the soups", default_value = "C1")]
+    symmetry: Symmetry,
+
+    #[arg(
+      long,
+      value_name = "FILE",
+      help = "File to append the seeds of soups with rare objects to [default: rare-soups.txt in the data directory]"
+    )]
+    rare_file: Option<PathBuf>,
+  },
+}
+
+impl Command {
+  pub fn run(&self, pattern: Option<Pattern>) -> Result<()> {
+    match self {
+      Command::Census { generations, distance } => {
+        let pattern =
+          pattern.ok_or_else(|| eyre!("Pass a pattern to take a census of with --pattern, --apgcode or --catalog"))?;
+        let distance = match distance {
+          Some(distance) => *distance,
+          None => Config::new()?.config.census_distance,
+        };
+        let mut cells: HashSet<Coord> = pattern.cells.into_iter().collect();
+        for _ in 0..*generations {
+          cells = life::step(&cells);
+        }
+        let cells: Vec<Coord> = cells.into_iter().sorted().collect();
+        println!("{}", serde_json::to_string_pretty(&Census::take(&cells, distance, None))?);
+      },
+      Command::Search { soups, seed, threads, distance, rare, symmetry, rare_file } => {
+        let threads = match threads {
+          Some(threads) => *threads,
+          None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        let distance = match distance {
+          Some(distance) => *distance,
+          None => Config::new()?.config.census_distance,
+        };
+        let search = Search { soups: *soups, seed: *seed, threads, distance, rare: *rare, symmetry: *symmetry };
+        let start = Instant::now();
+        let results = search.run();
+        let elapsed = start.elapsed().as_secs_f64();
+        eprintln!("Searched {} soups in {elapsed:.1}s ({:.1} soups/s)", results.soups, results.soups as f64 / elapsed);
+
+        let path = rare_file.clone().unwrap_or_else(|| get_data_dir().join("rare-soups.txt"));
+        // Soups that never stabilized are as interesting as the rare objects.
+        let lines: Vec<String> = results
+          .rare()
+          .flat_map(|tally| {
+            let code =
+              tally.object.apgcode.clone().unwrap_or_else(|| format!("unclassified_{}", tally.object.population));
+            tally.seeds.iter().map(move |seed| format!("{code} {seed}"))
+          })
+          .chain(results.unstabilized.iter().map(|seed| format!("unstabilized {seed}")))
+          .collect();
+        if !lines.is_empty() {
+          if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+          }
+          let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+          writeln!(file, "{}", lines.join("\n"))?;
+          eprintln!("Saved the seeds of {} soups with rare objects to {}", lines.len(), path.display());
+        }
+        println!("{}", serde_json::to_string_pretty(&results)?);
+      },
+    }
+    Ok(())
+  }
+}
+
+fn parse_density(s: &str) -> Result<f64, String> {
+  match s.parse::<f64>() {
+    Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+    _ => Err(format!("`{s}` is not a number from 0 to 1")),
+  }
+}
+
+/// Parses a size given as `WxH`, or as one number for a square.
+fn parse_size(s: &str) -> Result<(usize, usize), String> {
+  let (width, height) = s.split_once('x').unwrap_or((s, s));
+  match (width.trim().parse::<usize>(), height.trim().parse::<usize>()) {
+    (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+    _ => Err(format!("`{s}` is not a size such as 16x16 or 16")),
+  }
+}
+
+impl Cli {
+  /// Loads the starting pattern, if one was requested on the command line.
+  pub fn load_pattern(&self) -> Result<Option<Pattern>> {
+    if let Some(code) = &self.apgcode {
+      return Pattern::from_apgcode(code).map(Some);
+    }
+    if let Some(name) = &self.catalog {
+      return catalog::get(name).map(Some);
+    }
+    self.pattern.as_ref().map(|path| Pattern::load(&path.to_string_lossy())).transpose()
+  }
 }
@@ -1,15 +1,23 @@
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyEvent, MouseEvent};
-use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
   action::Action,
-  config::Config,
+  app::Mode,
+  config::{key_event_to_string, Config},
   tui::{Event, Frame},
 };
 
+pub mod census;
 pub mod fps;
+pub mod graph;
+pub mod help;
+pub mod library;
+pub mod picker;
+pub mod prompt;
+pub mod status;
 pub mod universe;
 
 pub trait Component {
@@ -45,4 +53,48 @@ pub trait Component {
     Ok(None)
   }
   fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()>;
+  /// Keys and mouse gestures that the component handles itself instead of through the keybindings config.
+  fn bindings(&self) -> Vec<Binding> {
+    vec![]
+  }
+}
+
+/// A binding that a component handles itself, listed in the help popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+  pub mode: Mode,
+  pub keys: String,
+  pub description: String,
+}
+
+impl Binding {
+  pub fn keys(mode: Mode, keys: &[KeyEvent], description: &str) -> Self {
+    let keys = keys.iter().map(key_event_to_string).collect::<Vec<_>>().join(", ");
+    Self { mode, keys, description: description.into() }
+  }
+
+  pub fn mouse(mode: Mode, gesture: &str, description: &str) -> Self {
+    Self { mode, keys: gesture.into(), description: description.into() }
+  }
+}
+
+/// Returns a rectangle of the given percentage size centered in `r`, for drawing popups.
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+  let popup_layout = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([
+      Constraint::Percentage((100 - percent_y) / 2),
+      Constraint::Percentage(percent_y),
+      Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .split(r);
+
+  Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([
+      Constraint::Percentage((100 - percent_x) / 2),
+      Constraint::Percentage(percent_x),
+      Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .split(popup_layout[1])[1]
 }
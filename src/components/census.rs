@@ -0,0 +1,130 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{centered_rect, Binding, Component, Frame};
+use crate::{
+  action::Action,
+  app::Mode,
+  census::{Census, Kind},
+};
+
+/// Popup with a table of the objects that the universe, or the selection, is made of.
+#[derive(Default)]
+pub struct CensusTable {
+  census: Census,
+  state: TableState,
+  visible: bool,
+  mode: Mode,
+  previous_mode: Mode,
+}
+
+impl CensusTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn open(&mut self, census: Census) -> Option<Action> {
+    self.census = census;
+    self.state.select((!self.census.objects.is_empty()).then_some(0));
+    self.visible = true;
+    self.previous_mode = self.mode;
+    Some(Action::EnterMode(Mode::Census))
+  }
+
+  fn close(&mut self) -> Option<Action> {
+    self.visible = false;
+    Some(Action::EnterMode(self.previous_mode))
+  }
+
+  fn select(&mut self, delta: isize) {
+    let last = self.census.objects.len().saturating_sub(1);
+    let selected = self.state.selected().unwrap_or_default().saturating_add_signed(delta).min(last);
+    self.state.select(Some(selected));
+  }
+}
+
+impl Component for CensusTable {
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    if !self.visible {
+      return Ok(None);
+    }
+    let action = match key.code {
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.select(-1);
+        None
+      },
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.select(1);
+        None
+      },
+      KeyCode::Esc | KeyCode::Char('q') => self.close(),
+      _ => None,
+    };
+    Ok(action)
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::Census(census) => return Ok(self.open(census)),
+      Action::EnterMode(mode) => self.mode = mode,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if !self.visible {
+      return Ok(());
+    }
+    let area = centered_rect(70, 80, rect);
+    f.render_widget(Clear, area);
+    let title = format!(" Census: {} objects, {} cells ", self.census.count(), self.census.population);
+    let block = Block::default()
+      .title(title)
+      .title(block::Title::from(" j/k: scroll, esc: close ".dim()).alignment(Alignment::Right))
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded);
+    let rows = self.census.objects.iter().map(|object| {
+      let kind = match object.kind {
+        Kind::StillLife => "still life",
+        Kind::Oscillator => "oscillator",
+        Kind::Spaceship => "spaceship",
+        Kind::Unclassified => "unclassified",
+      };
+      Row::new(vec![
+        object.count.to_string(),
+        object.name.clone().unwrap_or_default(),
+        object.apgcode.clone().unwrap_or_else(|| "-".into()),
+        kind.to_string(),
+        object.period.map(|period| period.to_string()).unwrap_or_else(|| "-".into()),
+        object.population.to_string(),
+      ])
+    });
+    let header = Row::new(vec!["count", "name", "apgcode", "kind", "period", "cells"])
+      .style(Style::default().add_modifier(Modifier::BOLD))
+      .bottom_margin(1);
+    let table = Table::new(rows)
+      .header(header)
+      .block(block)
+      .widths(&[
+        Constraint::Length(6),
+        Constraint::Length(22),
+        Constraint::Min(12),
+        Constraint::Length(12),
+        Constraint::Length(6),
+        Constraint::Length(6),
+      ])
+      .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_stateful_widget(table, area, &mut self.state);
+    Ok(())
+  }
+
+  fn bindings(&self) -> Vec<Binding> {
+    vec![
+      Binding::keys(Mode::Census, &[KeyCode::Up.into(), KeyCode::Char('k').into()], "Select the previous object"),
+      Binding::keys(Mode::Census, &[KeyCode::Down.into(), KeyCode::Char('j').into()], "Select the next object"),
+      Binding::keys(Mode::Census, &[KeyCode::Esc.into(), KeyCode::Char('q').into()], "Close"),
+    ]
+  }
+}
@@ -21,6 +21,10 @@ pub struct FpsCounter {
   render_start_time: Instant,
   render_frames: u32,
   render_fps: f64,
+
+  generations_start_time: Instant,
+  generations: usize,
+  generations_per_second: f64,
 }
 
 impl Default for FpsCounter {
@@ -38,6 +42,9 @@ impl FpsCounter {
       render_start_time: Instant::now(),
       render_frames: 0,
       render_fps: 0.0,
+      generations_start_time: Instant::now(),
+      generations: 0,
+      generations_per_second: 0.0,
     }
   }
 
@@ -64,12 +71,29 @@ impl FpsCounter {
     }
     Ok(())
   }
+
+  /// Counts generations as the universe reports them, and also when it reports none so that the rate drops to zero
+  /// while paused.
+  fn advanced(&mut self, n: usize) {
+    self.generations += n;
+    let now = Instant::now();
+    let elapsed = (now - self.generations_start_time).as_secs_f64();
+    if elapsed >= 1.0 {
+      self.generations_per_second = self.generations as f64 / elapsed;
+      self.generations_start_time = now;
+      self.generations = 0;
+    }
+  }
 }
 
 impl Component for FpsCounter {
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     if let Action::Tick = action {
-      self.app_tick()?
+      self.app_tick()?;
+      self.advanced(0);
+    };
+    if let Action::Advanced(n) = action {
+      self.advanced(n);
     };
     if let Action::Render = action {
       self.render_tick()?
@@ -88,7 +112,10 @@ impl Component for FpsCounter {
 
     let rect = rects[0];
 
-    let s = format!("{:.2} fps (app) {:.2} fps (render)", self.app_fps, self.render_fps);
+    let s = format!(
+      "{:.2} gen/s {:.2} fps (app) {:.2} fps (render)",
+      self.generations_per_second, self.app_fps, self.render_fps
+    );
     let block = Block::default().title(block::Title::from(s.dim()).alignment(Alignment::Right));
     f.render_widget(block, rect);
     Ok(())
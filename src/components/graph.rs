@@ -0,0 +1,159 @@
+use std::{collections::VecDeque, io::Write, path::PathBuf};
+
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use serde::Serialize;
+
+use super::{Component, Frame};
+use crate::{action::Action, config::Config};
+
+/// Number of generations that are remembered for the graph and the CSV export.
+const MAX_SAMPLES: usize = 100_000;
+
+/// How many cells were alive in a generation, and how many were born and died on the way to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Sample {
+  pub generation: usize,
+  pub population: usize,
+  pub births: usize,
+  pub deaths: usize,
+}
+
+/// Panel plotting the population over time, along with births and deaths per generation.
+#[derive(Default)]
+pub struct Graph {
+  config: Config,
+  samples: VecDeque<Sample>,
+  visible: bool,
+}
+
+impl Graph {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a sample, first forgetting the samples of the same or later generations when the universe went back in time.
+  fn record(&mut self, sample: Sample) {
+    while self.samples.back().is_some_and(|last| last.generation >= sample.generation) {
+      self.samples.pop_back();
+    }
+    self.samples.push_back(sample);
+    if self.samples.len() > MAX_SAMPLES {
+      self.samples.pop_front();
+    }
+  }
+
+  fn to_csv(&self) -> String {
+    let mut csv = String::from("generation,population,births,deaths\n");
+    for s in &self.samples {
+      csv.push_str(&format!("{},{},{},{}\n", s.generation, s.population, s.births, s.deaths));
+    }
+    csv
+  }
+
+  /// Writes every remembered sample to the population file, defaulting to `population.csv` in the data directory.
+  fn export(&self) -> Result<PathBuf> {
+    let config = &self.config.config;
+    let path = config.population_file.clone().unwrap_or_else(|| config._data_dir.join("population.csv"));
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::File::create(&path)?.write_all(self.to_csv().as_bytes())?;
+    Ok(path)
+  }
+}
+
+impl Component for Graph {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::Samples(samples) => {
+        for sample in samples {
+          self.record(sample);
+        }
+      },
+      Action::ToggleGraph => self.visible = !self.visible,
+      Action::ExportPopulation => {
+        match self.export() {
+          Ok(path) => log::info!("Exported the population to {}", path.display()),
+          Err(e) => return Ok(Some(Action::Error(format!("Failed to export the population: {:?}", e)))),
+        }
+      },
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    // Leave the top row to the FPS counter and the bottom rows to the toolbar and the status bar.
+    if !self.visible || rect.height < 12 || rect.width < 30 {
+      return Ok(());
+    }
+    let width = (rect.width * 2 / 5).clamp(30, 80);
+    let area = Rect::new(rect.right() - width, rect.y + 1, width, rect.height - 3);
+    f.render_widget(Clear, area);
+    let block = Block::default().title(" Population ").borders(Borders::ALL).border_type(BorderType::Rounded);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints([Constraint::Min(3), Constraint::Length(3), Constraint::Length(3)])
+      .split(inner);
+
+    // Braille markers fit two points in each column.
+    let shown: Vec<Sample> = self.samples.iter().rev().take(inner.width as usize * 2).rev().copied().collect();
+    let Some((first, last)) = shown.first().zip(shown.last()) else {
+      return Ok(());
+    };
+    let points: Vec<(f64, f64)> = shown.iter().map(|s| (s.generation as f64, s.population as f64)).collect();
+    let max = shown.iter().map(|s| s.population).max().unwrap_or_default().max(1);
+    let chart = Chart::new(vec![Dataset::default()
+      .name(format!("population {}", last.population))
+      .marker(symbols::Marker::Braille)
+      .graph_type(GraphType::Line)
+      .style(Style::default().fg(Color::Yellow))
+      .data(&points)])
+    .x_axis(
+      Axis::default()
+        .bounds([first.generation as f64, last.generation.max(first.generation + 1) as f64])
+        .labels(vec![first.generation.to_string().into(), last.generation.to_string().into()]),
+    )
+    .y_axis(Axis::default().bounds([0.0, max as f64]).labels(vec!["0".into(), max.to_string().into()]));
+    f.render_widget(chart, rows[0]);
+
+    // Sparklines draw one value per column, starting from the first.
+    let recent = &shown[shown.len().saturating_sub(inner.width as usize)..];
+    let births: Vec<u64> = recent.iter().map(|s| s.births as u64).collect();
+    let deaths: Vec<u64> = recent.iter().map(|s| s.deaths as u64).collect();
+    for (row, title, data, color) in [
+      (rows[1], format!("births {}", last.births), births, Color::Green),
+      (rows[2], format!("deaths {}", last.deaths), deaths, Color::Red),
+    ] {
+      let sparkline =
+        Sparkline::default().block(Block::default().title(title)).data(&data).style(Style::default().fg(color));
+      f.render_widget(sparkline, row);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_record_going_back() {
+    let mut graph = Graph::new();
+    for generation in 0..4 {
+      graph.record(Sample { generation, population: generation * 2, ..Sample::default() });
+    }
+    graph.record(Sample { generation: 2, population: 7, births: 1, deaths: 0 });
+    assert_eq!(graph.to_csv(), "generation,population,births,deaths\n0,0,0,0\n1,2,0,0\n2,7,1,0\n");
+  }
+}
@@ -0,0 +1,145 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{centered_rect, Binding, Component, Frame};
+use crate::{
+  action::Action,
+  app::Mode,
+  config::{key_event_to_string, Config},
+};
+
+/// Popup listing every key and mouse binding, from the keybindings config and from the components themselves.
+#[derive(Default)]
+pub struct Help {
+  config: Config,
+  bindings: Vec<Binding>,
+  lines: Vec<Line<'static>>,
+  scroll: u16,
+  visible: bool,
+  mode: Mode,
+  previous_mode: Mode,
+}
+
+impl Help {
+  /// Creates the popup with the bindings that the other components handle themselves.
+  pub fn new(bindings: Vec<Binding>) -> Self {
+    Self { bindings, ..Self::default() }
+  }
+
+  fn open(&mut self) {
+    self.lines = self.build_lines();
+    self.scroll = 0;
+    self.visible = true;
+  }
+
+  fn close(&mut self) -> Option<Action> {
+    self.visible = false;
+    Some(Action::EnterMode(self.previous_mode))
+  }
+
+  /// Lists the bindings of each mode, with the configured keys sorted by description followed by the keys that
+  /// components handle themselves.
+  fn build_lines(&self) -> Vec<Line<'static>> {
+    let own = self.bindings();
+    let component_bindings = self.bindings.iter().chain(own.iter());
+    let modes = self.config.keybindings.keys().chain(component_bindings.clone().map(|b| &b.mode)).unique().sorted();
+    let mut lines = vec![];
+    for mode in modes {
+      let mut entries: Vec<(String, String)> = vec![];
+      if let Some(keymap) = self.config.keybindings.get(mode) {
+        for (keys, action) in keymap.iter().sorted_by_key(|(keys, _)| keys.iter().map(key_event_to_string).join(" ")) {
+          let keys = keys.iter().map(key_event_to_string).join(" ");
+          let description = action.description();
+          match entries.iter_mut().find(|(_, d)| *d == description) {
+            Some((k, _)) => *k = format!("{k}, {keys}"),
+            None => entries.push((keys, description)),
+          }
+        }
+        entries.sort_by(|a, b| a.1.cmp(&b.1));
+      }
+      entries.extend(
+        component_bindings.clone().filter(|b| b.mode == *mode).map(|b| (b.keys.clone(), b.description.clone())),
+      );
+      if entries.is_empty() {
+        continue;
+      }
+      if !lines.is_empty() {
+        lines.push(Line::default());
+      }
+      lines.push(Line::from(Span::styled(format!("{mode:?}"), Style::default().add_modifier(Modifier::BOLD))));
+      let width = entries.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or_default();
+      for (keys, description) in entries {
+        lines.push(Line::from(vec![
+          Span::styled(format!("  {keys:>width$}  "), Style::default().fg(Color::Cyan)),
+          Span::raw(description),
+        ]));
+      }
+    }
+    lines
+  }
+}
+
+impl Component for Help {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    if !self.visible {
+      return Ok(None);
+    }
+    let action = match key.code {
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.scroll = self.scroll.saturating_sub(1);
+        None
+      },
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.scroll = (self.scroll + 1).min(self.lines.len().saturating_sub(1) as u16);
+        None
+      },
+      KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => self.close(),
+      _ => None,
+    };
+    Ok(action)
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::Help if !self.visible => {
+        self.open();
+        self.previous_mode = self.mode;
+        return Ok(Some(Action::EnterMode(Mode::Help)));
+      },
+      Action::EnterMode(mode) => self.mode = mode,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if !self.visible {
+      return Ok(());
+    }
+    let area = centered_rect(70, 80, rect);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+      .title(" Help ")
+      .title(block::Title::from(" j/k: scroll, esc: close ".dim()).alignment(Alignment::Right))
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded);
+    f.render_widget(Paragraph::new(self.lines.clone()).block(block).scroll((self.scroll, 0)), area);
+    Ok(())
+  }
+
+  fn bindings(&self) -> Vec<Binding> {
+    vec![
+      Binding::keys(Mode::Help, &[KeyCode::Up.into(), KeyCode::Char('k').into()], "Scroll up"),
+      Binding::keys(Mode::Help, &[KeyCode::Down.into(), KeyCode::Char('j').into()], "Scroll down"),
+      Binding::keys(Mode::Help, &[KeyCode::Esc.into(), KeyCode::Char('q').into(), KeyCode::Char('?').into()], "Close"),
+    ]
+  }
+}
@@ -0,0 +1,351 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{centered_rect, Binding, Component, Frame};
+use crate::{
+  action::Action,
+  app::Mode,
+  config::Config,
+  life,
+  parsers::{FileType, Pattern},
+};
+
+/// Popup for browsing the pattern files in the library directory.
+#[derive(Default)]
+pub struct Library {
+  command_tx: Option<UnboundedSender<Action>>,
+  config: Config,
+  dir: PathBuf,
+  files: Vec<PathBuf>,
+  matches: Vec<usize>,
+  query: String,
+  state: ListState,
+  previews: HashMap<PathBuf, Result<Pattern, String>>,
+  visible: bool,
+  mode: Mode,
+  previous_mode: Mode,
+}
+
+impl Library {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn open(&mut self) {
+    self.dir = self.config.config.library_dir.clone().unwrap_or_else(|| self.config.config._data_dir.join("patterns"));
+    self.files = scan(&self.dir);
+    self.previews.clear();
+    self.filter();
+    self.visible = true;
+  }
+
+  fn close(&mut self) -> Option<Action> {
+    self.visible = false;
+    Some(Action::EnterMode(self.previous_mode))
+  }
+
+  fn filter(&mut self) {
+    let mut scored: Vec<(i64, usize)> = self
+      .files
+      .iter()
+      .enumerate()
+      .filter_map(|(i, path)| fuzzy_match(&self.query, &self.display_name(path)).map(|score| (score, i)))
+      .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    self.matches = scored.into_iter().map(|(_, i)| i).collect();
+    self.state.select(if self.matches.is_empty() { None } else { Some(0) });
+  }
+
+  fn display_name(&self, path: &Path) -> String {
+    path.strip_prefix(&self.dir).unwrap_or(path).to_string_lossy().to_string()
+  }
+
+  fn next(&mut self) {
+    if !self.matches.is_empty() {
+      let i = self.state.selected().map_or(0, |i| (i + 1) % self.matches.len());
+      self.state.select(Some(i));
+    }
+  }
+
+  fn previous(&mut self) {
+    if !self.matches.is_empty() {
+      let i = self.state.selected().map_or(0, |i| (i + self.matches.len() - 1) % self.matches.len());
+      self.state.select(Some(i));
+    }
+  }
+
+  /// Parses the selected file, caching the result so that it is only read once per opening of the library.
+  fn selected(&mut self) -> Option<(PathBuf, &Result<Pattern, String>)> {
+    let path = self.files.get(*self.matches.get(self.state.selected()?)?)?.clone();
+    let preview = self
+      .previews
+      .entry(path.clone())
+      .or_insert_with(|| Pattern::from_file(&path.to_string_lossy()).map_err(|e| e.to_string()));
+    Some((path, preview))
+  }
+
+  fn send_selected(&mut self, action: fn(Pattern) -> Action) -> Result<()> {
+    if let Some((_, Ok(pattern))) = self.selected() {
+      let action = action(pattern.clone());
+      self.command_tx.as_ref().unwrap().send(action)?;
+    }
+    Ok(())
+  }
+}
+
+impl Component for Library {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    self.command_tx = Some(tx);
+    Ok(())
+  }
+
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    if !self.visible {
+      return Ok(None);
+    }
+    let action = match (key.code, key.modifiers) {
+      (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+        self.previous();
+        None
+      },
+      (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+        self.next();
+        None
+      },
+      (KeyCode::Enter, _) => {
+        self.send_selected(Action::LoadPattern)?;
+        self.close()
+      },
+      (KeyCode::Tab, _) => {
+        self.send_selected(Action::StampPattern)?;
+        self.close()
+      },
+      (KeyCode::Esc, _) => self.close(),
+      (KeyCode::Backspace, _) => {
+        self.query.pop();
+        self.filter();
+        None
+      },
+      (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+        self.query.push(c);
+        self.filter();
+        None
+      },
+      _ => None,
+    };
+    Ok(action)
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::OpenLibrary if !self.visible => {
+        self.open();
+        self.previous_mode = self.mode;
+        return Ok(Some(Action::EnterMode(Mode::Library)));
+      },
+      Action::EnterMode(mode) => self.mode = mode,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if !self.visible {
+      return Ok(());
+    }
+    let area = centered_rect(80, 80, rect);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+      .title(format!(" Library: {} ", self.dir.display()))
+      .title(block::Title::from(" enter: load, tab: stamp, esc: close ".dim()).alignment(Alignment::Right))
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rects = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![Constraint::Length(2), Constraint::Min(0)])
+      .split(inner);
+    let search = Paragraph::new(Line::from(vec!["/ ".dim(), Span::raw(self.query.as_str())]))
+      .block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(search, rects[0]);
+
+    if self.files.is_empty() {
+      let message = format!("No pattern files found in {}", self.dir.display());
+      f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }), rects[1]);
+      return Ok(());
+    }
+
+    let rects = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+      .split(rects[1]);
+
+    let items: Vec<ListItem> = self.matches.iter().map(|&i| ListItem::new(self.display_name(&self.files[i]))).collect();
+    let list =
+      List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)).highlight_symbol("> ");
+    f.render_stateful_widget(list, rects[0], &mut self.state);
+
+    let preview = Block::default().borders(Borders::LEFT);
+    let inner = preview.inner(rects[1]);
+    f.render_widget(preview, rects[1]);
+    let Some((path, pattern)) = self.selected() else {
+      return Ok(());
+    };
+    let pattern = match pattern {
+      Ok(pattern) => pattern,
+      Err(e) => {
+        f.render_widget(Paragraph::new(e.as_str()).wrap(Wrap { trim: true }).red(), inner);
+        return Ok(());
+      },
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+      pattern.name.clone().unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string()),
+      Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(author) = &pattern.author {
+      lines.push(Line::from(Span::styled(format!("by {author}"), Style::default().add_modifier(Modifier::ITALIC))));
+    }
+    let size = match pattern.area {
+      Some((x, y)) => format!("{x}x{y}, {} cells", pattern.cells.len()),
+      None => format!("{} cells", pattern.cells.len()),
+    };
+    lines.push(Line::from(Span::styled(size, Style::default().add_modifier(Modifier::DIM))));
+    for line in pattern.description.iter().flat_map(|d| d.lines()) {
+      lines.push(Line::from(line.to_string()));
+    }
+
+    let rects = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![Constraint::Length(lines.len().min(8) as u16 + 1), Constraint::Min(0)])
+      .split(inner);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), rects[0]);
+    f.render_widget(Thumbnail { cells: &pattern.cells }, rects[1]);
+    Ok(())
+  }
+
+  fn bindings(&self) -> Vec<Binding> {
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    vec![
+      Binding::keys(Mode::Library, &[KeyCode::Up.into(), ctrl('p')], "Select the previous file"),
+      Binding::keys(Mode::Library, &[KeyCode::Down.into(), ctrl('n')], "Select the next file"),
+      Binding::keys(Mode::Library, &[KeyCode::Enter.into()], "Load the selected file"),
+      Binding::keys(Mode::Library, &[KeyCode::Tab.into()], "Stamp the selected file onto the universe"),
+      Binding::keys(Mode::Library, &[KeyCode::Esc.into()], "Close"),
+      Binding { mode: Mode::Library, keys: "any character".into(), description: "Filter files by name".into() },
+    ]
+  }
+}
+
+/// Lists the pattern files in `dir` and its subdirectories.
+fn scan(dir: &Path) -> Vec<PathBuf> {
+  let mut files = vec![];
+  let mut dirs = vec![dir.to_path_buf()];
+  while let Some(dir) = dirs.pop() {
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+      continue;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+      let path = entry.path();
+      // Symlinked directories are skipped, since they can link back to a directory above them.
+      if entry.file_type().is_ok_and(|file_type| file_type.is_symlink()) && path.is_dir() {
+        continue;
+      }
+      if path.is_dir() {
+        dirs.push(path);
+      } else if FileType::from_filename(&path.to_string_lossy()).is_some() {
+        files.push(path);
+      }
+    }
+  }
+  files.sort();
+  files
+}
+
+/// Scores how well `query` matches `candidate` as a case insensitive subsequence, or `None` if it does not match.
+///
+/// Consecutive characters and characters at the start of a word score higher, and gaps between matched characters
+/// score lower.
+fn fuzzy_match(query: &str, candidate: &str) -> Option<i64> {
+  let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+  let mut score = 0;
+  let mut start = 0;
+  let mut last: Option<usize> = None;
+  for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+    let i = start + candidate[start..].iter().position(|&c| c == q)?;
+    score += 1;
+    if last.is_some_and(|last| last + 1 == i) {
+      score += 5;
+    } else if let Some(last) = last {
+      score -= (i - last) as i64;
+    }
+    if i == 0 || !candidate[i - 1].is_alphanumeric() {
+      score += 3;
+    }
+    last = Some(i);
+    start = i + 1;
+  }
+  Some(score)
+}
+
+/// Draws a pattern scaled down to fit the area, using half blocks for two rows of pixels per line.
+struct Thumbnail<'a> {
+  cells: &'a [life::Coord],
+}
+
+impl Widget for Thumbnail<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    let Some((x0, y0, x1, y1)) = life::bounding_box(self.cells) else {
+      return;
+    };
+    if area.width == 0 || area.height == 0 {
+      return;
+    }
+    let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+    let scale = w.div_ceil(area.width as usize).max(h.div_ceil(area.height as usize * 2)).max(1);
+    let mut pixels = vec![vec![false; w.div_ceil(scale)]; h.div_ceil(scale).next_multiple_of(2)];
+    for &(x, y) in self.cells {
+      pixels[(y - y0) as usize / scale][(x - x0) as usize / scale] = true;
+    }
+    for (row, (upper, lower)) in pixels.chunks(2).map(|rows| (&rows[0], &rows[1])).enumerate() {
+      for (col, ch) in upper.iter().zip(lower.iter()).enumerate().filter_map(|(col, pair)| {
+        match pair {
+          (true, true) => Some((col, '█')),
+          (true, false) => Some((col, '▀')),
+          (false, true) => Some((col, '▄')),
+          (false, false) => None,
+        }
+      }) {
+        buf.get_mut(area.left() + col as u16, area.top() + row as u16).set_char(ch);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fuzzy_match() {
+    assert!(fuzzy_match("ggg", "gosper-glider-gun.rle").is_some());
+    assert!(fuzzy_match("gun", "glider.rle").is_none());
+    assert!(fuzzy_match("", "glider.rle").is_some());
+    assert!(fuzzy_match("glid", "glider.rle") > fuzzy_match("glid", "gosper-glider-gun.rle"));
+    assert!(fuzzy_match("GLIDER", "glider.rle").is_some());
+  }
+}
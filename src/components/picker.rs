@@ -0,0 +1,145 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{centered_rect, Binding, Component, Frame};
+use crate::{action::Action, app::Mode, catalog, parsers::Pattern};
+
+/// Popup for choosing one of the patterns built into the binary.
+#[derive(Default)]
+pub struct Picker {
+  command_tx: Option<UnboundedSender<Action>>,
+  patterns: Vec<(&'static str, Pattern)>,
+  state: ListState,
+  visible: bool,
+  mode: Mode,
+  previous_mode: Mode,
+}
+
+impl Picker {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn next(&mut self) {
+    let i = self.state.selected().map_or(0, |i| (i + 1) % self.patterns.len().max(1));
+    self.state.select(Some(i));
+  }
+
+  fn previous(&mut self) {
+    let i = self.state.selected().map_or(0, |i| (i + self.patterns.len().max(1) - 1) % self.patterns.len().max(1));
+    self.state.select(Some(i));
+  }
+
+  fn close(&mut self) -> Option<Action> {
+    self.visible = false;
+    Some(Action::EnterMode(self.previous_mode))
+  }
+}
+
+impl Component for Picker {
+  fn init(&mut self, area: Rect) -> Result<()> {
+    self.patterns = catalog::patterns()?;
+    self.state.select(Some(0));
+    Ok(())
+  }
+
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    self.command_tx = Some(tx);
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    if !self.visible {
+      return Ok(None);
+    }
+    let action = match key.code {
+      KeyCode::Up | KeyCode::Char('k') => {
+        self.previous();
+        None
+      },
+      KeyCode::Down | KeyCode::Char('j') => {
+        self.next();
+        None
+      },
+      KeyCode::Enter => {
+        if let Some((_, pattern)) = self.state.selected().and_then(|i| self.patterns.get(i)) {
+          self.command_tx.as_ref().unwrap().send(Action::LoadPattern(pattern.clone()))?;
+        }
+        self.close()
+      },
+      KeyCode::Esc | KeyCode::Char('q') => self.close(),
+      _ => None,
+    };
+    Ok(action)
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::OpenPicker if !self.visible => {
+        self.visible = true;
+        self.previous_mode = self.mode;
+        return Ok(Some(Action::EnterMode(Mode::Picker)));
+      },
+      Action::EnterMode(mode) => self.mode = mode,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if !self.visible {
+      return Ok(());
+    }
+    let area = centered_rect(60, 60, rect);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+      .title(" Patterns ")
+      .title(block::Title::from(" enter: load, esc: close ".dim()).alignment(Alignment::Right))
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rects = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints(vec![Constraint::Length(22), Constraint::Min(0)])
+      .split(inner);
+
+    let items: Vec<ListItem> = self.patterns.iter().map(|(name, _)| ListItem::new(*name)).collect();
+    let list =
+      List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)).highlight_symbol("> ");
+    f.render_stateful_widget(list, rects[0], &mut self.state);
+
+    if let Some((name, pattern)) = self.state.selected().and_then(|i| self.patterns.get(i)) {
+      let mut lines = vec![Line::from(Span::styled(
+        pattern.name.clone().unwrap_or_else(|| name.to_string()),
+        Style::default().add_modifier(Modifier::BOLD),
+      ))];
+      if let Some(author) = &pattern.author {
+        lines.push(Line::from(Span::styled(format!("by {author}"), Style::default().add_modifier(Modifier::ITALIC))));
+      }
+      if let Some((x, y)) = pattern.area {
+        let size = format!("{x}x{y}, {} cells", pattern.cells.len());
+        lines.push(Line::from(Span::styled(size, Style::default().add_modifier(Modifier::DIM))));
+      }
+      lines.push(Line::default());
+      for line in pattern.description.iter().flat_map(|d| d.lines()) {
+        lines.push(Line::from(line.to_string()));
+      }
+      let details = Paragraph::new(lines).wrap(Wrap { trim: true }).block(Block::default().borders(Borders::LEFT));
+      f.render_widget(details, rects[1]);
+    }
+    Ok(())
+  }
+
+  fn bindings(&self) -> Vec<Binding> {
+    vec![
+      Binding::keys(Mode::Picker, &[KeyCode::Up.into(), KeyCode::Char('k').into()], "Select the previous pattern"),
+      Binding::keys(Mode::Picker, &[KeyCode::Down.into(), KeyCode::Char('j').into()], "Select the next pattern"),
+      Binding::keys(Mode::Picker, &[KeyCode::Enter.into()], "Load the selected pattern"),
+      Binding::keys(Mode::Picker, &[KeyCode::Esc.into(), KeyCode::Char('q').into()], "Close"),
+    ]
+  }
+}
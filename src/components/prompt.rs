@@ -0,0 +1,116 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Binding, Component, Frame};
+use crate::{action::Action, app::Mode};
+
+/// Popup asking for a number of generations, for the actions that need one.
+#[derive(Default)]
+pub struct Prompt {
+  command_tx: Option<UnboundedSender<Action>>,
+  /// Turns the number that was typed into the action to send.
+  action: Option<fn(usize) -> Action>,
+  title: &'static str,
+  input: String,
+  mode: Mode,
+  previous_mode: Mode,
+}
+
+impl Prompt {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn open(&mut self, title: &'static str, action: fn(usize) -> Action) -> Option<Action> {
+    self.previous_mode = self.mode;
+    self.action = Some(action);
+    self.title = title;
+    self.input.clear();
+    Some(Action::EnterMode(Mode::Prompt))
+  }
+
+  fn close(&mut self) -> Option<Action> {
+    self.action = None;
+    Some(Action::EnterMode(self.previous_mode))
+  }
+}
+
+impl Component for Prompt {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    self.command_tx = Some(tx);
+    Ok(())
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    let Some(action) = self.action else {
+      return Ok(None);
+    };
+    let action = match key.code {
+      KeyCode::Char(c) if c.is_ascii_digit() && self.input.len() < 18 => {
+        self.input.push(c);
+        None
+      },
+      KeyCode::Backspace => {
+        self.input.pop();
+        None
+      },
+      KeyCode::Enter => {
+        if let Ok(n) = self.input.parse() {
+          self.command_tx.as_ref().unwrap().send(action(n))?;
+        }
+        self.close()
+      },
+      KeyCode::Esc => self.close(),
+      _ => None,
+    };
+    Ok(action)
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::PromptStepN if self.action.is_none() => {
+        return Ok(self.open(" Step generations ", Action::StepN));
+      },
+      Action::PromptRunUntil if self.action.is_none() => {
+        return Ok(self.open(" Run until generation ", Action::RunUntil));
+      },
+      Action::EnterMode(mode) => self.mode = mode,
+      _ => {},
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if self.action.is_none() {
+      return Ok(());
+    }
+    let width = 34.min(rect.width);
+    let area = Rect::new(
+      rect.x + (rect.width - width) / 2,
+      rect.y + rect.height.saturating_sub(3) / 2,
+      width,
+      3.min(rect.height),
+    );
+    f.render_widget(Clear, area);
+    let block = Block::default()
+      .title(self.title)
+      .title(block::Title::from(" enter, esc ".dim()).alignment(Alignment::Right))
+      .borders(Borders::ALL)
+      .border_type(BorderType::Rounded);
+    f.render_widget(
+      Paragraph::new(Line::from(vec![Span::raw(self.input.as_str()), "_".slow_blink()])).block(block),
+      area,
+    );
+    Ok(())
+  }
+
+  fn bindings(&self) -> Vec<Binding> {
+    vec![
+      Binding { mode: Mode::Prompt, keys: "0-9, backspace".into(), description: "Edit the number".into() },
+      Binding::keys(Mode::Prompt, &[KeyCode::Enter.into()], "Confirm"),
+      Binding::keys(Mode::Prompt, &[KeyCode::Esc.into()], "Cancel"),
+    ]
+  }
+}
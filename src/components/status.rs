@@ -0,0 +1,89 @@
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use serde::Serialize;
+
+use super::{
+  universe::{Periodicity, RenderMode, Tool},
+  Component, Frame,
+};
+use crate::action::Action;
+
+/// What the universe reports about itself on every frame.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Status {
+  pub generation: usize,
+  pub population: usize,
+  pub rule: String,
+  pub width: usize,
+  pub height: usize,
+  /// Seed of the random soup that the universe started from, if it did.
+  pub seed: Option<u64>,
+  pub paused: bool,
+  /// Generation that the universe is running until.
+  pub run_until: Option<usize>,
+  /// Generations per second, or `None` when running as fast as possible.
+  pub speed: Option<f64>,
+  pub render_mode: RenderMode,
+  pub tool: Tool,
+  /// Cell under the mouse, or under the cursor in edit mode, as `(x, y)`.
+  pub pointer: Option<(usize, usize)>,
+  /// How the universe, or the selection, repeats itself, once it does.
+  pub periodicity: Option<Periodicity>,
+}
+
+/// Bar along the bottom of the screen showing the state of the universe.
+#[derive(Default)]
+pub struct StatusBar {
+  status: Option<Status>,
+}
+
+impl StatusBar {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl Component for StatusBar {
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    if let Action::Status(status) = action {
+      self.status = Some(status);
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    let Some(status) = &self.status else {
+      return Ok(());
+    };
+    if rect.height < 2 {
+      return Ok(());
+    }
+    let state = match (status.paused, status.run_until) {
+      (true, _) => "paused".to_string(),
+      (false, Some(generation)) => format!("running until {generation}"),
+      (false, None) => "running".to_string(),
+    };
+    let speed = match status.speed {
+      Some(speed) => format!("{speed} gen/s"),
+      None => "max speed".to_string(),
+    };
+    // Fields that only apply sometimes are left out when they do not.
+    let fields = [
+      Some(format!("generation {}", status.generation)),
+      Some(format!("population {}", status.population)),
+      Some(status.rule.clone()),
+      Some(format!("torus {}x{}", status.width, status.height)),
+      status.seed.map(|seed| format!("seed {seed}")),
+      Some(state),
+      status.periodicity.map(|periodicity| periodicity.to_string()),
+      Some(speed),
+      Some(format!("{:?}", status.render_mode)),
+      Some(status.tool.name().to_string()),
+      status.pointer.map(|(x, y)| format!("({x}, {y})")),
+    ];
+    let text = fields.into_iter().flatten().collect::<Vec<_>>().join(" │ ");
+    let area = Rect::new(rect.x, rect.bottom() - 1, rect.width, 1);
+    f.render_widget(Paragraph::new(format!(" {text}")).style(Style::default().add_modifier(Modifier::REVERSED)), area);
+    Ok(())
+  }
+}
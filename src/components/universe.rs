@@ -1,19 +1,52 @@
-use std::{iter, path::PathBuf};
+use std::{
+  collections::HashSet,
+  io::Write,
+  iter,
+  time::{Duration, Instant},
+};
 
 // Based on https://rustwasm.github.io/book/game-of-life/introduction.html
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEventKind};
+use crossterm::event::{KeyModifiers, MouseButton, MouseEventKind};
 use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
 use ratatui::{prelude::*, widgets::*};
+use serde::Deserialize;
 use tokio::sync::mpsc::UnboundedSender;
 
-use super::{Component, Frame};
+use self::{
+  camera::Camera,
+  heatmap::Heatmap,
+  history::{Change, History, Snapshot},
+  period::PeriodDetector,
+  render::View,
+  selection::Selection,
+  theme::{Theme, THEMES},
+};
+pub use self::{
+  period::Periodicity,
+  render::{ColorSupport, Overlay, RenderMode},
+  tools::Tool,
+};
+use super::{graph::Sample, status::Status, Binding, Component, Frame};
 use crate::{
   action::Action,
+  app::Mode,
+  census::Census,
   config::Config,
   parsers::{Cell, Pattern},
+  soup,
 };
 
+mod camera;
+mod heatmap;
+mod history;
+mod period;
+mod render;
+mod selection;
+mod theme;
+mod tools;
+
 #[derive(Default)]
 enum HalfBlock {
   #[default]
@@ -22,6 +55,17 @@ enum HalfBlock {
   Lower,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ResizeAnchor {
+  #[default]
+  Center,
+  TopLeft,
+  Pattern,
+}
+
+const MIN_SPEED: f64 = 0.125;
+const MAX_SPEED: f64 = 100_000.0;
+
 #[derive(Default)]
 pub struct Universe {
   command_tx: Option<UnboundedSender<Action>>,
@@ -29,31 +73,114 @@ pub struct Universe {
   width: usize,
   height: usize,
   cells: Vec<Vec<Cell>>,
-  filename: Option<PathBuf>,
+  pattern: Option<Pattern>,
   paused: bool,
+  generation: usize,
+  run_until: Option<usize>,
+  /// Generations left to advance while paused, a tick at a time so that the terminal stays responsive.
+  steps: usize,
+  tick_rate: f64,
+  speed: f64,
+  /// Fraction of a generation left over from previous ticks, for speeds that are not a multiple of the tick rate.
+  budget: f64,
+  max_speed: bool,
   half_block: HalfBlock,
+  tool: Tool,
+  shape: Option<((isize, isize), (isize, isize))>,
+  last_mouse: Option<(u16, u16)>,
+  toolbar: Vec<(Rect, Tool)>,
+  eraser_size: usize,
+  mode: Mode,
+  camera: Camera,
+  area: Rect,
+  follow: bool,
+  cursor: Option<(usize, usize)>,
+  clipboard: Option<Pattern>,
+  target: Option<(usize, usize)>,
+  pasting: bool,
+  preview: HashSet<(usize, usize)>,
+  selection: Option<Selection>,
+  mark: Option<(usize, usize)>,
+  select_from: Option<(usize, usize)>,
+  select_dragged: bool,
+  drag: Option<(u16, u16)>,
+  render_mode: RenderMode,
+  colors: ColorSupport,
+  themes: Vec<Theme>,
+  theme: usize,
+  overlay: Overlay,
+  heatmap: Heatmap,
+  history: History,
+  period: PeriodDetector,
+  seed: u64,
+  pause_on_period: bool,
+  samples: Vec<Sample>,
+  /// Kind of the edit in progress, so that edits of the same kind in a row are undone together.
+  edit_group: Option<EditGroup>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditGroup {
+  Draw,
+  Resize,
 }
 
 impl Universe {
-  pub fn new(filename: Option<PathBuf>) -> Self {
-    Self { filename, ..Self::default() }
-  }
-
-  pub fn pattern(&mut self, filename: &str) -> Result<()> {
-    let pattern = Pattern::from_file(filename)?;
-    let origin = (self.width / 2, self.height / 2);
-    for (x, y) in pattern.cells {
-      let x = (x + origin.0 as isize) as usize;
-      let y = (y + origin.1 as isize) as usize;
-      if y < self.height && x < self.width {
-        self.cells[y][x] = Cell::Alive(0);
+  pub fn new(pattern: Option<Pattern>, tick_rate: f64) -> Self {
+    Self { clipboard: pattern.clone(), pattern, tick_rate, speed: tick_rate, ..Self::default() }
+  }
+
+  pub fn stamp(&mut self, pattern: &Pattern, center: (usize, usize)) {
+    for (x, y) in self.placed(pattern, center) {
+      self.cells[y][x] = Cell::Alive(0);
+    }
+  }
+
+  fn placed(&self, pattern: &Pattern, (cx, cy): (usize, usize)) -> Vec<(usize, usize)> {
+    let Some((x0, y0, x1, y1)) = crate::life::bounding_box(&pattern.cells) else {
+      return vec![];
+    };
+    let origin = (cx as isize - (x0 + x1) / 2, cy as isize - (y0 + y1) / 2);
+    let (width, height) = (self.width.max(1) as isize, self.height.max(1) as isize);
+    pattern
+      .cells
+      .iter()
+      .map(|&(x, y)| ((x + origin.0).rem_euclid(width) as usize, (y + origin.1).rem_euclid(height) as usize))
+      .collect()
+  }
+
+  fn copy(&mut self) {
+    if let Some(pattern) = self.selected_pattern() {
+      self.clipboard = Some(pattern);
+    }
+  }
+
+  fn selected_pattern(&self) -> Option<Pattern> {
+    let selection = self.selection?;
+    let (x0, y0, _, _) = selection.bounds();
+    let cells = selection
+      .cells()
+      .filter(|&(x, y)| matches!(self.cells.get(y).and_then(|row| row.get(x)), Some(Cell::Alive(_))))
+      .map(|(x, y)| ((x - x0) as isize, (y - y0) as isize))
+      .collect();
+    Some(Pattern { cells, area: Some(selection.size()), ..Pattern::default() })
+  }
+
+  fn edit_selection(&mut self, f: impl Fn(Cell) -> Cell) {
+    let Some(selection) = self.selection else {
+      return;
+    };
+    self.record_edit(None);
+    for (x, y) in selection.cells() {
+      if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+        *cell = f(*cell);
       }
     }
-    Ok(())
   }
 
-  pub fn tick(&mut self) {
+  pub fn tick(&mut self) -> Sample {
     let mut next = self.cells.clone();
+    let (mut population, mut births, mut deaths) = (0, 0, 0);
 
     for row in 0..self.height {
       for col in 0..self.width {
@@ -78,11 +205,121 @@ impl Universe {
           (Cell::Dead(i), _) => Cell::Dead(i.saturating_add(1)),
         };
 
+        match (cell, next_cell) {
+          (Cell::Dead(_), Cell::Alive(_)) => births += 1,
+          (Cell::Alive(_), Cell::Dead(_)) => deaths += 1,
+          _ => {},
+        }
+        if let Cell::Alive(_) = next_cell {
+          population += 1;
+        }
         next[row][col] = next_cell;
       }
     }
 
     self.cells = next;
+    self.generation += 1;
+    Sample { generation: self.generation, population, births, deaths }
+  }
+
+  fn sample(&mut self) {
+    let population = self.live_cells().count();
+    self.samples.push(Sample { generation: self.generation, population, births: 0, deaths: 0 });
+  }
+
+  /// Advances `n` generations, keeping the overlays and the camera up to date.
+  ///
+  /// Stops early when the universe is paused by reaching the generation it was running until, or when it has taken
+  /// longer than `deadline`. Returns how many generations were advanced.
+  fn advance(&mut self, n: usize, deadline: Option<Duration>) -> usize {
+    let start = Instant::now();
+    let mut advanced = 0;
+    while advanced < n {
+      self.history.record(self.snapshot(), Change::Generation);
+      self.edit_group = None;
+      let sample = self.tick();
+      self.samples.push(sample);
+      advanced += 1;
+      if self.overlay == Overlay::Heatmap {
+        self.heatmap.record(&self.cells);
+      }
+      if self.period.record(&self.cells, self.generation, self.selection).is_some() && self.pause_on_period {
+        self.run_until = None;
+        self.steps = 0;
+        self.paused = true;
+        break;
+      }
+      if self.run_until == Some(self.generation) {
+        self.run_until = None;
+        self.paused = true;
+        break;
+      }
+      if deadline.is_some_and(|deadline| start.elapsed() > deadline) {
+        self.budget = 0.0;
+        break;
+      }
+    }
+    if self.follow {
+      self.follow_center_of_mass();
+    }
+    advanced
+  }
+
+  fn snapshot(&self) -> Snapshot {
+    Snapshot::new(&self.cells, self.generation)
+  }
+
+  fn record_edit(&mut self, group: Option<EditGroup>) {
+    self.period.clear();
+    if !self.cells.is_empty() && (group.is_none() || group != self.edit_group) {
+      self.history.record(self.snapshot(), Change::Edit);
+    }
+    self.edit_group = group;
+  }
+
+  fn restore(&mut self, snapshot: Snapshot) {
+    (self.width, self.height, self.generation) = (snapshot.width, snapshot.height, snapshot.generation);
+    self.cells = snapshot.rows();
+    self.paused = true;
+    self.run_until = None;
+    self.steps = 0;
+    self.edit_group = None;
+    self.period.clear();
+    self.clamp_positions();
+    self.sample();
+  }
+
+  fn clamp_positions(&mut self) {
+    let (width, height) = (self.width, self.height);
+    let clamp = |(x, y): (usize, usize)| (x.min(width.saturating_sub(1)), y.min(height.saturating_sub(1)));
+    self.cursor = self.cursor.map(clamp);
+    self.target = self.target.map(clamp);
+    self.mark = self.mark.map(clamp);
+    self.selection = self.selection.map(|selection| Selection::new(clamp(selection.from), clamp(selection.to)));
+  }
+
+  /// Advances the generations that are due at the current speed, using most of a tick at most so that the terminal
+  /// stays responsive.
+  fn run(&mut self) -> usize {
+    let deadline = Some(Duration::from_secs_f64(0.8 / self.tick_rate));
+    if self.steps > 0 {
+      let advanced = self.advance(self.steps, deadline);
+      self.steps = self.steps.saturating_sub(advanced);
+      return advanced;
+    }
+    if self.max_speed {
+      return self.advance(usize::MAX, deadline);
+    }
+    self.budget += self.speed / self.tick_rate;
+    let due = self.budget.floor();
+    self.budget -= due;
+    self.advance(due as usize, deadline)
+  }
+
+  fn set_speed(&mut self, speed: f64) {
+    self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    self.max_speed = false;
+    self.budget = 0.0;
   }
 
   fn get_index(&self, row: usize, column: usize) -> usize {
@@ -107,21 +344,300 @@ impl Universe {
     }
     count
   }
+
+  fn resize(&mut self, width: usize, height: usize) {
+    if (width, height) == (self.width, self.height) {
+      return;
+    }
+    self.record_edit(Some(EditGroup::Resize));
+    let (dx, dy) = match self.config.config.resize_anchor {
+      ResizeAnchor::Center => {
+        ((width as isize - self.width as isize) / 2, (height as isize - self.height as isize) / 2)
+      },
+      ResizeAnchor::TopLeft => (0, 0),
+      ResizeAnchor::Pattern => {
+        let live = self.cells.iter().enumerate().flat_map(|(y, row)| {
+          row
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| matches!(c, Cell::Alive(_)))
+            .map(move |(x, _)| (x as isize, y as isize))
+        });
+        match crate::life::bounding_box(&live.collect::<Vec<_>>()) {
+          Some((x0, y0, x1, y1)) => ((width as isize - x0 - x1) / 2, (height as isize - y0 - y1) / 2),
+          None => (0, 0),
+        }
+      },
+    };
+    let mut cells = vec![vec![Cell::EMPTY; width]; height];
+    for (y, row) in self.cells.iter().enumerate() {
+      for (x, cell) in row.iter().enumerate() {
+        let (x, y) = (x as isize + dx, y as isize + dy);
+        if (0..width as isize).contains(&x) && (0..height as isize).contains(&y) {
+          cells[y as usize][x as usize] = *cell;
+        }
+      }
+    }
+    (self.width, self.height, self.cells) = (width, height, cells);
+    self.clamp_positions();
+  }
+
+  fn viewport(&self) -> (usize, usize) {
+    let (pw, ph) = self.render_mode.pixels_per_char();
+    (self.area.width as usize * pw, self.area.height as usize * ph)
+  }
+
+  fn pixel_at(&self, column: u16, row: u16) -> (usize, usize) {
+    let (pw, ph) = self.render_mode.pixels_per_char();
+    (column.saturating_sub(self.area.x) as usize * pw, row.saturating_sub(self.area.y) as usize * ph)
+  }
+
+  fn cycle_render_mode(&mut self) {
+    let (cx, cy) = self.view_center();
+    self.render_mode = self.render_mode.next();
+    let (pw, ph) = self.viewport();
+    if self.config.config.world_size.is_none() && (pw > self.width || ph > self.height) {
+      let (dx, dy) = ((pw.max(self.width) - self.width) / 2, (ph.max(self.height) - self.height) / 2);
+      self.resize(pw.max(self.width), ph.max(self.height));
+      self.camera.center_on((cx + dx) as isize, (cy + dy) as isize, pw, ph, self.width, self.height);
+    } else {
+      self.camera.center_on(cx as isize, cy as isize, pw, ph, self.width, self.height);
+    }
+  }
+
+  fn view_center(&self) -> (usize, usize) {
+    let (pw, ph) = self.viewport();
+    let (vw, vh) = self.camera.visible(pw, ph);
+    ((self.camera.x + vw.min(self.width) / 2) % self.width, (self.camera.y + vh.min(self.height) / 2) % self.height)
+  }
+
+  fn char_at(&self, x: usize, y: usize) -> Option<(u16, u16)> {
+    let (pw, ph) = self.viewport();
+    let (vw, vh) = self.camera.visible(pw, ph);
+    let dx = (x + self.width - self.camera.x % self.width.max(1)) % self.width.max(1);
+    let dy = (y + self.height - self.camera.y % self.height.max(1)) % self.height.max(1);
+    if dx >= vw.min(self.width) || dy >= vh.min(self.height) {
+      return None;
+    }
+    let (cw, ch) = self.camera.cell_size();
+    let k = self.camera.block_size();
+    let (ppc_w, ppc_h) = self.render_mode.pixels_per_char();
+    Some(((dx / k * cw / ppc_w) as u16, (dy / k * ch / ppc_h) as u16))
+  }
+
+  fn move_cursor(&mut self, dx: isize, dy: isize) {
+    let Some((x, y)) = self.cursor else {
+      return;
+    };
+    let x = (x as isize + dx).rem_euclid(self.width.max(1) as isize) as usize;
+    let y = (y as isize + dy).rem_euclid(self.height.max(1) as isize) as usize;
+    self.cursor = Some((x, y));
+    self.target = self.cursor;
+    if let Some(mark) = self.mark {
+      self.selection = Some(Selection::new(mark, (x, y)));
+    }
+    if self.char_at(x, y).is_none() {
+      let (pw, ph) = self.viewport();
+      self.camera.center_on(x as isize, y as isize, pw, ph, self.width, self.height);
+    }
+  }
+
+  fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.cells.iter().enumerate().flat_map(|(y, row)| {
+      row.iter().enumerate().filter(|(_, c)| matches!(c, Cell::Alive(_))).map(move |(x, _)| (x, y))
+    })
+  }
+
+  fn view(&self, mode: RenderMode, colors: ColorSupport) -> View<'_> {
+    let theme = self.themes.get(self.theme).copied().unwrap_or_default();
+    View {
+      cells: &self.cells,
+      camera: self.camera,
+      mode,
+      colors,
+      theme,
+      overlay: self.overlay,
+      trails: self.config.config.trail_length,
+      heatmap: &self.heatmap,
+      glyphs: self.config.config.ascii_glyphs,
+      selection: self.selection,
+      preview: &self.preview,
+    }
+  }
+
+  fn load_themes(&mut self) {
+    let config = &self.config.config;
+    let base = config.theme.as_deref().and_then(Theme::get).unwrap_or_default();
+    if let Some(name) = config.theme.as_deref().filter(|name| Theme::get(name).is_none()) {
+      log::error!("Unknown theme `{name}`, expected one of: {}", THEMES.iter().map(|t| t.name).join(", "));
+    }
+    self.themes = THEMES.to_vec();
+    let custom = self.config.styles.get(&Mode::Home).and_then(|styles| base.customize(styles));
+    self.theme = self.themes.iter().position(|theme| theme.name == base.name).unwrap_or_default();
+    if let Some(custom) = custom {
+      self.theme = self.themes.len();
+      self.themes.push(custom);
+    }
+    if let Some(gradient) = config.age_gradient {
+      self.themes.iter_mut().for_each(|theme| theme.gradient = gradient);
+    }
+  }
+
+  fn dump_frame(&self) -> Result<()> {
+    let (pw, ph) = self.viewport();
+    let path = self.config.config.dump_file.clone().unwrap_or_else(|| self.config.config._data_dir.join("frames.txt"));
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", self.view(RenderMode::Ascii, ColorSupport::None).to_text(pw, ph))?;
+    Ok(())
+  }
+
+  /// Returns the cells under a square brush of `size` characters centered on the mouse as `(row, column)`, depending
+  /// on which half of the characters at its top and bottom edges is being drawn.
+  fn cells_under(&self, column: u16, row: u16, size: usize) -> Vec<(usize, usize)> {
+    let (px, py) = self.pixel_at(column, row);
+    let (pw, ph) = self.render_mode.pixels_per_char();
+    let (before, after) = ((size.max(1) - 1) / 2, size.max(1) / 2);
+    let rows = match self.half_block {
+      HalfBlock::Upper => py..py + ph.div_ceil(2),
+      HalfBlock::Lower => py + ph / 2..py + ph,
+      HalfBlock::Full => py..py + ph,
+    };
+    let rows = rows.start.saturating_sub(before * ph)..rows.end + after * ph;
+    rows
+      .cartesian_product(px.saturating_sub(before * pw)..px + (after + 1) * pw)
+      .filter_map(|(py, px)| self.camera.cell_at(px, py, self.width, self.height))
+      .map(|(x, y)| (y, x))
+      .unique()
+      .collect()
+  }
+
+  fn offset_at(&self, column: u16, row: u16) -> (isize, isize) {
+    let (px, py) = self.pixel_at(column, row);
+    let (_, ph) = self.render_mode.pixels_per_char();
+    let py = if matches!(self.half_block, HalfBlock::Lower) { py + ph / 2 } else { py };
+    let (ox, oy, k) = self.camera.block_at(px, py);
+    ((ox + k / 2) as isize, (oy + k / 2) as isize)
+  }
+
+  fn shape_cells(&self) -> Vec<(usize, usize)> {
+    let Some((from, to)) = self.shape else {
+      return vec![];
+    };
+    self
+      .tool
+      .shape(from, to)
+      .into_iter()
+      .filter(|&(ox, oy)| ox >= 0 && oy >= 0 && (ox as usize) < self.width && (oy as usize) < self.height)
+      .map(|(ox, oy)| ((self.camera.x + ox as usize) % self.width, (self.camera.y + oy as usize) % self.height))
+      .collect()
+  }
+
+  /// Sends `action` for each cell under the brush along the way from where the mouse last was, so that fast drags do
+  /// not leave gaps.
+  fn brush(&mut self, column: u16, row: u16, size: usize, action: fn(usize, usize) -> Action) -> Result<()> {
+    let (from_column, from_row) = self.last_mouse.replace((column, row)).unwrap_or((column, row));
+    let path = tools::line((from_column as isize, from_row as isize), (column as isize, row as isize));
+    let cells: Vec<_> =
+      path.into_iter().flat_map(|(c, r)| self.cells_under(c as u16, r as u16, size)).unique().collect();
+    for (r, c) in cells {
+      self.command_tx.as_ref().unwrap().send(action(r, c))?;
+    }
+    Ok(())
+  }
+
+  fn fit_pattern(&mut self) {
+    let live: Vec<_> = self.live_cells().map(|(x, y)| (x as isize, y as isize)).collect();
+    if let Some((x0, y0, x1, y1)) = crate::life::bounding_box(&live) {
+      let (pw, ph) = self.viewport();
+      self.camera.fit((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize, pw, ph);
+      self.camera.center_on((x0 + x1) / 2, (y0 + y1) / 2, pw, ph, self.width, self.height);
+    }
+  }
+
+  /// Moves the camera to the center of mass of the live cells, so that spaceships stay in view.
+  ///
+  /// Positions are measured from the current center of the viewport the short way around the universe, so that a
+  /// pattern crossing an edge does not make the camera jump.
+  fn follow_center_of_mass(&mut self) {
+    let (cx, cy) = self.view_center();
+    let wrap = |d: isize, n: usize| (d + n as isize / 2).rem_euclid(n as isize) - n as isize / 2;
+    let (mut sx, mut sy, mut n) = (0, 0, 0);
+    for (x, y) in self.live_cells() {
+      sx += wrap(x as isize - cx as isize, self.width);
+      sy += wrap(y as isize - cy as isize, self.height);
+      n += 1;
+    }
+    if n > 0 {
+      let (pw, ph) = self.viewport();
+      self.camera.center_on(cx as isize + sx / n, cy as isize + sy / n, pw, ph, self.width, self.height);
+    }
+  }
+
+  fn reset(&mut self) {
+    self.record_edit(None);
+    self.cells = iter::repeat_n(iter::repeat_n(Cell::EMPTY, self.width).collect(), self.height).collect();
+    self.heatmap.clear();
+    self.generation = 0;
+    self.run_until = None;
+    self.steps = 0;
+    let (pw, ph) = self.viewport();
+    self.camera.center_on(self.width as isize / 2, self.height as isize / 2, pw, ph, self.width, self.height);
+    if let Some(pattern) = self.pattern.take() {
+      self.stamp(&pattern, self.view_center());
+      self.pattern = Some(pattern);
+    } else {
+      let config = &self.config.config;
+      let (width, height) = config.soup_size.unwrap_or((self.width, self.height));
+      let (width, height) = config.symmetry.size(width.min(self.width), height.min(self.height));
+      let density = config.soup_density.clamp(0.0, 1.0);
+      let cells = soup::random(&mut StdRng::seed_from_u64(self.seed), width, height, density, config.symmetry);
+      // Like a pattern, the soup goes in the middle of the view.
+      let (cx, cy) = self.view_center();
+      let (x0, y0) = (cx + self.width - width / 2, cy + self.height - height / 2);
+      for (x, y) in cells {
+        self.cells[(y0 + y) % self.height][(x0 + x) % self.width] = Cell::Alive(0);
+      }
+    }
+    self.sample();
+  }
+
+  fn draw_toolbar(&mut self, f: &mut Frame<'_>, area: Rect) {
+    self.toolbar.clear();
+    let width: u16 = Tool::ALL.iter().map(|tool| tool.name().len() as u16 + 2).sum();
+    if area.width < width || area.height < 3 {
+      return;
+    }
+    let mut x = area.right() - width;
+    for tool in Tool::ALL {
+      let rect = Rect::new(x, area.bottom() - 2, tool.name().len() as u16 + 2, 1);
+      let style = if tool == self.tool { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+      f.render_widget(Paragraph::new(format!(" {} ", tool.name())).style(style), rect);
+      self.toolbar.push((rect, tool));
+      x += rect.width;
+    }
+  }
 }
 
 impl Component for Universe {
   fn init(&mut self, area: Rect) -> Result<()> {
-    (self.width, self.height) = (area.width as usize, area.height as usize * 2);
-    self.cells = iter::repeat(iter::repeat(Cell::Dead(0)).take(self.width).collect()).take(self.height).collect();
-    if let Some(f) = self.filename.clone() {
-      self.pattern(&f.to_string_lossy())?;
-    } else {
-      for row in 0..self.height {
-        for col in 0..self.width {
-          self.cells[row][col] = if rand::random::<bool>() { Cell::Alive(0) } else { Cell::Dead(0) };
-        }
-      }
+    self.area = area;
+    self.render_mode = self.config.config.render_mode.unwrap_or_else(RenderMode::detect);
+    self.colors = self.config.config.colors.unwrap_or_else(ColorSupport::detect);
+    self.load_themes();
+    self.history = History::new(self.config.config.history_megabytes * 1024 * 1024);
+    self.eraser_size = self.config.config.eraser_size.max(1);
+    self.pause_on_period = self.config.config.pause_on_period;
+    self.seed = self.config.config.seed.unwrap_or_else(rand::random);
+    if let Some(speed) = self.config.config.speed {
+      self.set_speed(speed);
     }
+    self.overlay = self.config.config.overlay;
+    self.heatmap = Heatmap::new(self.config.config.heatmap_window);
+    (self.width, self.height) = self.config.config.world_size.unwrap_or(self.viewport());
+    self.reset();
     Ok(())
   }
 
@@ -136,155 +652,400 @@ impl Component for Universe {
   }
 
   fn handle_mouse_events(&mut self, mouse: crossterm::event::MouseEvent) -> Result<Option<Action>> {
+    if !matches!(self.mode, Mode::Home | Mode::Edit) {
+      return Ok(None);
+    }
+    let (px, py) = self.pixel_at(mouse.column, mouse.row);
+    let cell = self.camera.cell_at(px, py, self.width, self.height);
+    if matches!(mouse.kind, MouseEventKind::Moved | MouseEventKind::Down(_) | MouseEventKind::Drag(_)) && cell.is_some()
+    {
+      self.target = cell;
+    }
     match mouse.kind {
-      MouseEventKind::Down(MouseButton::Left) => {
-        match self.half_block {
-          HalfBlock::Upper => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2, mouse.column as usize))?;
-          },
-          HalfBlock::Lower => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2 + 1, mouse.column as usize))?
-          },
-          HalfBlock::Full => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2, mouse.column as usize))?;
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2 + 1, mouse.column as usize))?
-          },
+      MouseEventKind::Down(MouseButton::Left) if self.pasting => {
+        self.command_tx.as_ref().unwrap().send(Action::StampClipboard)?;
+      },
+      MouseEventKind::Down(MouseButton::Right) if self.pasting => self.pasting = false,
+      MouseEventKind::Down(MouseButton::Left) if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+        self.select_from = cell;
+        self.select_dragged = false;
+      },
+      MouseEventKind::Drag(MouseButton::Left) if self.select_from.is_some() => {
+        if let (Some(from), Some(to)) = (self.select_from, cell) {
+          self.selection = Some(Selection::new(from, to));
+          self.mark = None;
+          self.select_dragged = true;
         }
       },
-      MouseEventKind::Drag(MouseButton::Left) => {
-        match self.half_block {
-          HalfBlock::Upper => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2, mouse.column as usize))?;
-          },
-          HalfBlock::Lower => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2 + 1, mouse.column as usize))?
-          },
-          HalfBlock::Full => {
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2, mouse.column as usize))?;
-            self.command_tx.as_ref().unwrap().send(Action::Insert(mouse.row as usize * 2 + 1, mouse.column as usize))?
-          },
+      MouseEventKind::Down(MouseButton::Left) if self.toolbar.iter().any(|(rect, _)| contains(rect, &mouse)) => {
+        if let Some(&(_, tool)) = self.toolbar.iter().find(|(rect, _)| contains(rect, &mouse)) {
+          self.tool = tool;
+        }
+      },
+      MouseEventKind::Down(MouseButton::Left) if self.tool == Tool::Fill => {
+        if let Some((x, y)) = cell {
+          self.command_tx.as_ref().unwrap().send(Action::Fill(y, x))?;
+        }
+      },
+      MouseEventKind::Down(MouseButton::Left) if self.tool != Tool::Freehand => {
+        let offset = self.offset_at(mouse.column, mouse.row);
+        self.shape = Some((offset, offset));
+      },
+      MouseEventKind::Drag(MouseButton::Left) if self.shape.is_some() => {
+        if let Some((from, _)) = self.shape {
+          self.shape = Some((from, self.offset_at(mouse.column, mouse.row)));
         }
       },
+      MouseEventKind::Down(button @ (MouseButton::Left | MouseButton::Right))
+      | MouseEventKind::Drag(button @ (MouseButton::Left | MouseButton::Right)) => {
+        if matches!(mouse.kind, MouseEventKind::Down(_)) {
+          self.last_mouse = None;
+        }
+        match button {
+          MouseButton::Left => self.brush(mouse.column, mouse.row, 1, Action::Insert)?,
+          _ => self.brush(mouse.column, mouse.row, self.eraser_size, Action::Remove)?,
+        }
+      },
+      MouseEventKind::Down(MouseButton::Middle) => self.drag = Some((mouse.column, mouse.row)),
+      MouseEventKind::Drag(MouseButton::Middle) => {
+        if let Some((column, row)) = self.drag.replace((mouse.column, mouse.row)) {
+          // Move the universe along with the mouse, so the camera moves the opposite way.
+          let (cw, ch) = self.camera.cell_size();
+          let (pw, ph) = self.render_mode.pixels_per_char();
+          let k = self.camera.block_size();
+          let dx = (column as isize - mouse.column as isize) * (pw * k / cw) as isize;
+          let dy = (row as isize - mouse.row as isize) * (ph * k / ch) as isize;
+          self.camera.pan(dx, dy, self.width, self.height);
+        }
+      },
+      MouseEventKind::Up(MouseButton::Middle) => self.drag = None,
+      MouseEventKind::Up(MouseButton::Left | MouseButton::Right) => {
+        // Shapes and clicks are applied here rather than sent as actions, so that each one is a single edit and the
+        // next stroke does not join it.
+        let shape = self.shape_cells();
+        if !shape.is_empty() {
+          self.record_edit(None);
+          for (x, y) in shape {
+            if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+              *cell = Cell::Alive(0);
+            }
+          }
+        }
+        self.shape = None;
+        if self.select_from.take().is_some() && !self.select_dragged {
+          self.record_edit(None);
+          for (r, c) in self.cells_under(mouse.column, mouse.row, 1) {
+            if let Some(cell) = self.cells.get_mut(r).and_then(|row| row.get_mut(c)) {
+              *cell = Cell::from(!matches!(cell, Cell::Alive(_)));
+            }
+          }
+        }
+        self.edit_group = None;
+      },
+      MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+        let delta = if mouse.kind == MouseEventKind::ScrollUp { 1 } else { -1 };
+        let (px, py) = self.pixel_at(mouse.column, mouse.row);
+        self.camera.zoom_by(delta, px, py, self.width, self.height);
+      },
       _ => (),
     };
     Ok(None)
   }
 
-  fn handle_key_events(&mut self, key: crossterm::event::KeyEvent) -> Result<Option<Action>> {
-    log::info!("{:?}", key);
-    if key.kind == KeyEventKind::Press {
-      match key.code {
-        KeyCode::Char(' ') => Ok(Some(Action::TogglePause)),
-        KeyCode::Char('1') => Ok(Some(Action::UseHalfBlockFull)),
-        KeyCode::Char('2') => Ok(Some(Action::UseHalfBlockUpper)),
-        KeyCode::Char('3') => Ok(Some(Action::UseHalfBlockLower)),
-        _ => Ok(None),
-      }
-    } else {
-      Ok(None)
-    }
-  }
-
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     match action {
-      Action::Tick => {
-        if !self.paused {
-          self.tick()
+      Action::Render => {
+        if !self.samples.is_empty() {
+          self.command_tx.as_ref().unwrap().send(Action::Samples(std::mem::take(&mut self.samples)))?;
+        }
+        return Ok(Some(Action::Status(Status {
+          generation: self.generation,
+          population: self.live_cells().count(),
+          rule: "B3/S23".into(),
+          width: self.width,
+          height: self.height,
+          seed: self.pattern.is_none().then_some(self.seed),
+          paused: self.paused && self.steps == 0,
+          run_until: self.run_until.or((self.steps > 0).then_some(self.generation + self.steps)),
+          speed: (!self.max_speed).then_some(self.speed),
+          render_mode: self.render_mode,
+          tool: self.tool,
+          pointer: self.target,
+          periodicity: self.period.periodicity(self.selection),
+        })));
+      },
+      Action::Tick if !self.paused || self.steps > 0 => {
+        let n = self.run();
+        if n > 0 {
+          return Ok(Some(Action::Advanced(n)));
+        }
+      },
+      // Stepping forward after stepping back replays the history, so that edits that were stepped back over are kept.
+      Action::Step if self.history.can_go_forward() => {
+        if let Some((snapshot, _)) = self.history.forward(self.snapshot()) {
+          self.restore(snapshot);
+        }
+      },
+      Action::StepBack => {
+        if let Some((snapshot, _)) = self.history.back(self.snapshot()) {
+          self.restore(snapshot);
+        }
+      },
+      Action::Undo => {
+        if let Some(snapshot) = self.history.undo(self.snapshot()) {
+          self.restore(snapshot);
         }
       },
+      Action::Redo => {
+        if let Some(snapshot) = self.history.redo(self.snapshot()) {
+          self.restore(snapshot);
+        }
+      },
+      Action::Step => {
+        self.paused = true;
+        self.steps = 0;
+        return Ok(Some(Action::Advanced(self.advance(1, None))));
+      },
+      Action::StepN(n) => {
+        self.paused = true;
+        self.run_until = None;
+        self.steps = n;
+      },
+      Action::Faster => self.set_speed(self.speed * 2.0),
+      Action::Slower => self.set_speed(self.speed / 2.0),
+      Action::SetSpeed(0) => self.paused = true,
+      Action::SetSpeed(speed) => {
+        self.set_speed(speed as f64);
+        self.paused = false;
+      },
+      Action::ToggleMaxSpeed => self.max_speed = !self.max_speed,
+      Action::RunUntil(generation) if generation > self.generation => {
+        self.run_until = Some(generation);
+        self.paused = false;
+      },
       Action::Insert(r, c) => {
-        self.cells[r][c] = Cell::Alive(0);
+        self.record_edit(Some(EditGroup::Draw));
+        if let Some(cell) = self.cells.get_mut(r).and_then(|row| row.get_mut(c)) {
+          *cell = Cell::Alive(0);
+        }
+      },
+      Action::Remove(r, c) => {
+        self.record_edit(Some(EditGroup::Draw));
+        if let Some(cell) = self.cells.get_mut(r).and_then(|row| row.get_mut(c)) {
+          *cell = Cell::EMPTY;
+        }
+      },
+      Action::Toggle(r, c) => {
+        self.record_edit(Some(EditGroup::Draw));
+        if let Some(cell) = self.cells.get_mut(r).and_then(|row| row.get_mut(c)) {
+          *cell = Cell::from(!matches!(cell, Cell::Alive(_)));
+        }
+      },
+      Action::Fill(r, c) => {
+        self.record_edit(None);
+        for (x, y) in tools::flood_fill(&self.cells, (c, r)) {
+          self.cells[y][x] = Cell::from(!matches!(self.cells[y][x], Cell::Alive(_)));
+        }
+      },
+      Action::UseTool(tool) => self.tool = tool,
+      Action::CycleTool => self.tool = self.tool.next(),
+      Action::GrowEraser => self.eraser_size += 1,
+      Action::ShrinkEraser => self.eraser_size = self.eraser_size.saturating_sub(1).max(1),
+      Action::TakeCensus => {
+        // A selection is a region of its own, so only the whole universe wraps around.
+        let (cells, torus): (Vec<_>, _) = match self.selection {
+          Some(selection) => {
+            let cells = selection
+              .cells()
+              .filter(|&(x, y)| matches!(self.cells.get(y).and_then(|row| row.get(x)), Some(Cell::Alive(_))))
+              .collect();
+            (cells, None)
+          },
+          None => (self.live_cells().collect(), Some((self.width, self.height))),
+        };
+        let cells: Vec<_> = cells.into_iter().map(|(x, y)| (x as isize, y as isize)).collect();
+        return Ok(Some(Action::Census(Census::take(&cells, self.config.config.census_distance, torus))));
+      },
+      Action::TogglePauseOnPeriod => self.pause_on_period = !self.pause_on_period,
+      Action::TogglePause => {
+        self.paused = !self.paused;
+        self.run_until = None;
+        self.steps = 0;
+      },
+      Action::Resize(w, h) => {
+        self.area = Rect::new(0, 0, w, h);
+        if self.config.config.world_size.is_none() {
+          let (pw, ph) = self.viewport();
+          self.resize(pw, ph);
+        }
+      },
+      Action::PanLeft | Action::PanRight | Action::PanUp | Action::PanDown => {
+        let (pw, ph) = self.viewport();
+        let (vw, vh) = self.camera.visible(pw, ph);
+        let (dx, dy) = ((vw / 8).max(1) as isize, (vh / 8).max(1) as isize);
+        let (dx, dy) = match action {
+          Action::PanLeft => (-dx, 0),
+          Action::PanRight => (dx, 0),
+          Action::PanUp => (0, -dy),
+          _ => (0, dy),
+        };
+        self.camera.pan(dx, dy, self.width, self.height);
+      },
+      Action::ZoomIn | Action::ZoomOut => {
+        let (pw, ph) = self.viewport();
+        let delta = if action == Action::ZoomIn { 1 } else { -1 };
+        self.camera.zoom_by(delta, pw / 2, ph / 2, self.width, self.height);
+      },
+      Action::FitPattern => self.fit_pattern(),
+      Action::CycleRenderMode => self.cycle_render_mode(),
+      Action::CycleOverlay => {
+        self.overlay = self.overlay.next();
+        self.heatmap.clear();
+      },
+      Action::CycleTheme => self.theme = (self.theme + 1) % self.themes.len().max(1),
+      Action::DumpFrame => {
+        if let Err(e) = self.dump_frame() {
+          return Ok(Some(Action::Error(format!("Failed to dump frame: {:?}", e))));
+        }
+      },
+      Action::ToggleFollow => {
+        self.follow = !self.follow;
+        if self.follow {
+          self.follow_center_of_mass();
+        }
+      },
+      Action::Reset => self.reset(),
+      Action::Reseed => {
+        self.pattern = None;
+        self.reset();
+      },
+      Action::NextSeed => {
+        self.seed = self.seed.wrapping_add(1);
+        self.pattern = None;
+        self.reset();
       },
-      Action::TogglePause => self.paused = !self.paused,
-      Action::Resize(w, h) => self.init(Rect::new(0, 0, w, h))?,
       Action::UseHalfBlockUpper => self.half_block = HalfBlock::Upper,
       Action::UseHalfBlockLower => self.half_block = HalfBlock::Lower,
       Action::UseHalfBlockFull => self.half_block = HalfBlock::Full,
+      Action::EnterMode(mode) => {
+        self.mode = mode;
+        match mode {
+          Mode::Edit if self.cursor.is_none() => {
+            self.cursor = Some(self.view_center());
+            self.target = self.cursor;
+          },
+          Mode::Home => self.cursor = None,
+          _ => {},
+        }
+      },
+      Action::MoveCursor(dx, dy) => self.move_cursor(dx, dy),
+      Action::ToggleCell => {
+        if let Some((x, y)) = self.cursor {
+          self.record_edit(None);
+          if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *cell = Cell::from(!matches!(cell, Cell::Alive(_)));
+          }
+        }
+      },
+      Action::StampClipboard => {
+        if let Some(pattern) = self.clipboard.clone() {
+          self.record_edit(None);
+          self.stamp(&pattern, self.target.unwrap_or_else(|| self.view_center()));
+        }
+        self.pasting = false;
+      },
+      Action::ToggleMark => {
+        if let (None, Some(cursor)) = (self.mark.take(), self.cursor) {
+          self.mark = Some(cursor);
+          self.selection = Some(Selection::new(cursor, cursor));
+        }
+      },
+      Action::SelectNone => {
+        self.selection = None;
+        self.mark = None;
+        self.pasting = false;
+      },
+      Action::Copy => self.copy(),
+      Action::CopyRle => {
+        self.copy();
+        if let Some(pattern) = self.selected_pattern() {
+          if let Err(e) = crate::tui::copy_to_clipboard(&pattern.to_rle()) {
+            return Ok(Some(Action::Error(format!("Failed to copy to the clipboard: {:?}", e))));
+          }
+        }
+      },
+      Action::PasteText(text) if matches!(self.mode, Mode::Home | Mode::Edit) => {
+        match Pattern::parse(&text) {
+          Ok(pattern) => {
+            self.clipboard = Some(pattern);
+            self.pasting = true;
+          },
+          Err(e) => return Ok(Some(Action::Error(format!("Failed to parse the pasted pattern: {:?}", e)))),
+        }
+      },
+      Action::Cut => {
+        self.copy();
+        self.edit_selection(|cell| if matches!(cell, Cell::Alive(_)) { Cell::EMPTY } else { cell });
+      },
+      Action::Paste => self.pasting = self.clipboard.is_some(),
+      Action::ClearSelection => {
+        self.edit_selection(|cell| if matches!(cell, Cell::Alive(_)) { Cell::EMPTY } else { cell })
+      },
+      Action::FillSelection(density) => {
+        self.edit_selection(|_| Cell::from(rand::random::<f64>() * 100.0 < density as f64))
+      },
+      Action::InvertSelection => self.edit_selection(|cell| Cell::from(!matches!(cell, Cell::Alive(_)))),
+      Action::LoadPattern(pattern) => {
+        self.clipboard = Some(pattern.clone());
+        self.pattern = Some(pattern);
+        self.reset();
+      },
+      Action::StampPattern(pattern) => {
+        self.record_edit(None);
+        self.stamp(&pattern, self.view_center());
+        self.clipboard = Some(pattern);
+      },
       _ => {},
     }
     Ok(None)
   }
 
   fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
-    let mut grid = vec![];
-    let young = Color::Rgb(255, 213, 57);
-    let old = Color::Rgb(202, 32, 77);
-    let sick = Color::Reset;
-    let dead = Color::Reset;
-    for (y, (line1, line2)) in self.cells.iter().tuples().enumerate() {
-      for (x, (c1, c2)) in line1.iter().zip(line2.iter()).enumerate() {
-        match (c1, c2) {
-          (Cell::Alive(0), Cell::Alive(0)) => {
-            grid.push((x, y, '▀', Style::default().fg(young).bg(young)));
-          },
-          (Cell::Alive(0), Cell::Alive(_)) => {
-            grid.push((x, y, '▀', Style::default().fg(young).bg(old)));
-          },
-          (Cell::Alive(_), Cell::Alive(0)) => {
-            grid.push((x, y, '▀', Style::default().fg(old).bg(young)));
-          },
-          (Cell::Alive(_), Cell::Alive(_)) => {
-            grid.push((x, y, '▀', Style::default().fg(old).bg(old)));
-          },
-          (Cell::Dead(0), Cell::Alive(0)) => {
-            grid.push((x, y, '▄', Style::default().bg(sick).fg(young)));
-          },
-          (Cell::Dead(_), Cell::Alive(0)) => {
-            grid.push((x, y, '▄', Style::default().bg(dead).fg(young)));
-          },
-          (Cell::Dead(0), Cell::Alive(_)) => {
-            grid.push((x, y, '▄', Style::default().bg(sick).fg(old)));
-          },
-          (Cell::Dead(_), Cell::Alive(_)) => {
-            grid.push((x, y, '▄', Style::default().bg(dead).fg(old)));
-          },
-          (Cell::Alive(0), Cell::Dead(0)) => {
-            grid.push((x, y, '▀', Style::default().fg(young).bg(sick)));
-          },
-          (Cell::Alive(0), Cell::Dead(_)) => {
-            grid.push((x, y, '▀', Style::default().fg(young).bg(dead)));
-          },
-          (Cell::Alive(_), Cell::Dead(0)) => {
-            grid.push((x, y, '▀', Style::default().fg(old).bg(sick)));
-          },
-          (Cell::Alive(_), Cell::Dead(_)) => {
-            grid.push((x, y, '▀', Style::default().fg(old).bg(dead)));
-          },
-          (Cell::Dead(0), Cell::Dead(0)) => {
-            grid.push((x, y, ' ', Style::default().fg(sick).bg(sick)));
-          },
-          (Cell::Dead(0), Cell::Dead(_)) => {
-            grid.push((x, y, ' ', Style::default().fg(sick).bg(dead)));
-          },
-          (Cell::Dead(_), Cell::Dead(0)) => {
-            grid.push((x, y, ' ', Style::default().fg(dead).bg(sick)));
-          },
-          (Cell::Dead(_), Cell::Dead(_)) => {
-            grid.push((x, y, ' ', Style::default().fg(dead).bg(dead)));
-          },
-        }
+    self.area = area;
+    self.preview = match (&self.clipboard, self.target) {
+      (Some(pattern), Some(target)) if self.pasting => self.placed(pattern, target).into_iter().collect(),
+      _ => HashSet::new(),
+    };
+    self.preview.extend(self.shape_cells());
+    f.render_widget(self.view(self.render_mode, self.colors), area);
+    self.draw_toolbar(f, area);
+    if let Some((column, row)) = self.cursor.filter(|_| self.mode == Mode::Edit).and_then(|(x, y)| self.char_at(x, y)) {
+      let (x, y) = (area.x + column, area.y + row);
+      if x < area.right() && y < area.bottom() {
+        f.render_widget(
+          Block::default().style(Style::default().add_modifier(Modifier::REVERSED)),
+          Rect::new(x, y, 1, 1),
+        );
+        f.set_cursor(x, y);
       }
     }
-    f.render_widget(Grid { grid }, area);
     Ok(())
   }
-}
 
-struct Grid {
-  grid: Vec<(usize, usize, char, Style)>,
+  fn bindings(&self) -> Vec<Binding> {
+    vec![
+      Binding::mouse(Mode::Home, "left click, left drag", "Draw with the current tool"),
+      Binding::mouse(Mode::Home, "click on the toolbar", "Pick a drawing tool"),
+      Binding::mouse(Mode::Home, "right click, right drag", "Erase cells"),
+      Binding::mouse(Mode::Home, "shift click", "Toggle cells"),
+      Binding::mouse(Mode::Home, "shift drag", "Select a rectangle"),
+      Binding::mouse(Mode::Home, "left click while pasting", "Paste the clipboard"),
+      Binding::mouse(Mode::Home, "middle drag", "Move the camera"),
+      Binding::mouse(Mode::Home, "scroll", "Zoom around the mouse"),
+    ]
+  }
 }
 
-impl Widget for Grid {
-  fn render(self, area: Rect, buf: &mut Buffer) {
-    for (x, y, ch, style) in self.grid.iter() {
-      if *x >= area.width as usize || *y >= area.height as usize {
-        continue;
-      }
-      let x = area.left() + *x as u16;
-      let y = area.top() + *y as u16;
-      let cell = buf.get_mut(x, y);
-      cell.set_char(*ch);
-      cell.set_style(*style);
-    }
-  }
+fn contains(rect: &Rect, mouse: &crossterm::event::MouseEvent) -> bool {
+  (rect.left()..rect.right()).contains(&mouse.column) && (rect.top()..rect.bottom()).contains(&mouse.row)
 }
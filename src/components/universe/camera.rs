@@ -0,0 +1,99 @@
+/// Lowest zoom level, where each pixel summarizes a 16x16 square of cells.
+pub const MIN_ZOOM: i8 = -4;
+/// Highest zoom level, where each cell is drawn with a 4x4 square of pixels.
+pub const MAX_ZOOM: i8 = 3;
+
+/// The part of the universe that is visible and the scale it is drawn at.
+///
+/// The viewport is measured in pixels, which are the smallest units the render mode can draw, e.g. the two halves of a
+/// terminal cell when drawing with half blocks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Camera {
+  /// Column of the cell drawn in the top left corner of the viewport.
+  pub x: usize,
+  /// Row of the cell drawn in the top left corner of the viewport.
+  pub y: usize,
+  /// Positive levels draw each cell with several pixels, negative levels summarize several cells in each pixel.
+  pub zoom: i8,
+}
+
+impl Camera {
+  /// Number of pixels along the width and height of one cell.
+  pub fn cell_size(&self) -> (usize, usize) {
+    match self.zoom {
+      ..=0 => (1, 1),
+      1 => (1, 2),
+      2 => (2, 2),
+      _ => (4, 4),
+    }
+  }
+
+  /// Number of cells along each side of the square that is summarized by one pixel.
+  pub fn block_size(&self) -> usize {
+    if self.zoom < 0 {
+      1 << -self.zoom
+    } else {
+      1
+    }
+  }
+
+  /// Returns the offset from the camera of the square of cells covered by a pixel, and the length of its side.
+  pub fn block_at(&self, px: usize, py: usize) -> (usize, usize, usize) {
+    let (cw, ch) = self.cell_size();
+    let k = self.block_size();
+    (px / cw * k, py / ch * k, k)
+  }
+
+  /// Number of columns and rows of cells that fit in a viewport of the given size in pixels.
+  pub fn visible(&self, pw: usize, ph: usize) -> (usize, usize) {
+    let (cw, ch) = self.cell_size();
+    let k = self.block_size();
+    (pw / cw * k, ph / ch * k)
+  }
+
+  /// Returns the cell under a pixel, or the cell in the middle of the square the pixel summarizes.
+  ///
+  /// Returns `None` if the pixel is past the edge of a universe that is smaller than the viewport.
+  pub fn cell_at(&self, px: usize, py: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+    let (ox, oy, k) = self.block_at(px, py);
+    let (ox, oy) = (ox + k / 2, oy + k / 2);
+    (ox < width && oy < height).then_some(((self.x + ox) % width, (self.y + oy) % height))
+  }
+
+  /// Moves the camera by a number of cells, wrapping around the edges of the universe.
+  pub fn pan(&mut self, dx: isize, dy: isize, width: usize, height: usize) {
+    self.x = (self.x as isize + dx).rem_euclid(width.max(1) as isize) as usize;
+    self.y = (self.y as isize + dy).rem_euclid(height.max(1) as isize) as usize;
+  }
+
+  /// Moves the camera so that the cell at `(x, y)` is in the middle of a viewport of the given size in pixels.
+  pub fn center_on(&mut self, x: isize, y: isize, pw: usize, ph: usize, width: usize, height: usize) {
+    let (vw, vh) = self.visible(pw, ph);
+    let (vw, vh) = (vw.min(width), vh.min(height));
+    (self.x, self.y) = (0, 0);
+    self.pan(x - vw as isize / 2, y - vh as isize / 2, width, height);
+  }
+
+  /// Changes the zoom level, keeping the cell under the pixel at `(px, py)` in the same place.
+  pub fn zoom_by(&mut self, delta: i8, px: usize, py: usize, width: usize, height: usize) {
+    let zoom = (self.zoom + delta).clamp(MIN_ZOOM, MAX_ZOOM);
+    if zoom == self.zoom {
+      return;
+    }
+    let (ox, oy, _) = self.block_at(px, py);
+    self.zoom = zoom;
+    let (nx, ny, _) = self.block_at(px, py);
+    self.pan(ox as isize - nx as isize, oy as isize - ny as isize, width, height);
+  }
+
+  /// Picks the highest zoom level at which a `w` by `h` rectangle of cells fits in the viewport.
+  pub fn fit(&mut self, w: usize, h: usize, pw: usize, ph: usize) {
+    self.zoom = (MIN_ZOOM..=MAX_ZOOM)
+      .rev()
+      .find(|&zoom| {
+        let (vw, vh) = Camera { zoom, ..*self }.visible(pw, ph);
+        w <= vw && h <= vh
+      })
+      .unwrap_or(MIN_ZOOM);
+  }
+}
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use ratatui::style::Color;
+
+use super::theme::mix;
+use crate::parsers::Cell;
+
+/// Colors of the heatmap, from cells that were rarely alive to cells that were always alive.
+const HEAT: [(f64, Color); 4] = [
+  (0.0, Color::Rgb(30, 20, 110)),
+  (0.33, Color::Rgb(140, 40, 200)),
+  (0.66, Color::Rgb(240, 70, 40)),
+  (1.0, Color::Rgb(255, 240, 130)),
+];
+
+/// Counts how many of the last `window` generations each cell was alive in.
+#[derive(Debug, Default)]
+pub struct Heatmap {
+  window: usize,
+  width: usize,
+  counts: Vec<u32>,
+  /// Indices of the live cells in each recorded generation, oldest first.
+  generations: VecDeque<Vec<usize>>,
+}
+
+impl Heatmap {
+  pub fn new(window: usize) -> Self {
+    Self { window: window.max(1), ..Self::default() }
+  }
+
+  /// Forgets every recorded generation.
+  pub fn clear(&mut self) {
+    self.counts.clear();
+    self.generations.clear();
+  }
+
+  /// Records which cells are alive in the current generation, forgetting the oldest one if the window is full.
+  pub fn record(&mut self, cells: &[Vec<Cell>]) {
+    let width = cells.first().map_or(0, |row| row.len());
+    if width != self.width || self.counts.len() != width * cells.len() {
+      self.clear();
+      self.width = width;
+      self.counts = vec![0; width * cells.len()];
+    }
+    let live: Vec<usize> =
+      cells.iter().flatten().enumerate().filter(|(_, cell)| matches!(cell, Cell::Alive(_))).map(|(i, _)| i).collect();
+    for &i in &live {
+      self.counts[i] += 1;
+    }
+    self.generations.push_back(live);
+    while self.generations.len() > self.window {
+      for i in self.generations.pop_front().unwrap_or_default() {
+        self.counts[i] -= 1;
+      }
+    }
+  }
+
+  /// Fraction of the recorded generations in which the cell at `(x, y)` was alive.
+  pub fn heat(&self, x: usize, y: usize) -> f64 {
+    match self.counts.get(y * self.width + x) {
+      Some(&count) if !self.generations.is_empty() => count as f64 / self.generations.len() as f64,
+      _ => 0.0,
+    }
+  }
+}
+
+/// Color of a cell that was alive for `heat` of the time.
+pub fn heat_color(heat: f64) -> Color {
+  let i = HEAT.iter().position(|&(stop, _)| heat <= stop).unwrap_or(HEAT.len() - 1).max(1);
+  let ((from, from_color), (to, to_color)) = (HEAT[i - 1], HEAT[i]);
+  mix(from_color, to_color, (heat - from) / (to - from))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_window() {
+    let mut heatmap = Heatmap::new(2);
+    let blinker = |vertical: bool| {
+      (0..3).map(|y| (0..3).map(|x| Cell::from(if vertical { x == 1 } else { y == 1 })).collect()).collect::<Vec<_>>()
+    };
+    heatmap.record(&blinker(true));
+    assert_eq!(heatmap.heat(1, 0), 1.0);
+    heatmap.record(&blinker(false));
+    assert_eq!((heatmap.heat(1, 1), heatmap.heat(1, 0), heatmap.heat(0, 0)), (1.0, 0.5, 0.0));
+    heatmap.record(&blinker(false));
+    heatmap.record(&blinker(false));
+    assert_eq!((heatmap.heat(1, 0), heatmap.heat(0, 1)), (0.0, 1.0));
+  }
+
+  #[test]
+  fn test_heat_color() {
+    assert_eq!(heat_color(0.0), HEAT[0].1);
+    assert_eq!(heat_color(1.0), HEAT[3].1);
+    assert_eq!(heat_color(0.66), HEAT[2].1);
+  }
+}
@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+
+use crate::parsers::Cell;
+
+/// What happened to the universe after a snapshot was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+  /// Cells were drawn, a pattern was stamped or loaded, or the universe was resized or reset.
+  Edit,
+  /// The universe advanced one generation.
+  Generation,
+}
+
+/// The state of the universe at some point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+  pub width: usize,
+  pub height: usize,
+  pub generation: usize,
+  pub cells: Vec<Cell>,
+}
+
+impl Snapshot {
+  pub fn new(cells: &[Vec<Cell>], generation: usize) -> Self {
+    let (width, height) = (cells.first().map_or(0, |row| row.len()), cells.len());
+    Self { width, height, generation, cells: cells.concat() }
+  }
+
+  pub fn rows(&self) -> Vec<Vec<Cell>> {
+    self.cells.chunks(self.width.max(1)).map(|row| row.to_vec()).collect()
+  }
+
+  fn bytes(&self) -> usize {
+    std::mem::size_of::<Self>() + self.cells.len() * std::mem::size_of::<Cell>()
+  }
+}
+
+/// Snapshots of the universe before each change, for undoing edits and stepping back through generations.
+///
+/// Each entry holds a state and the change that led away from it, so that stepping back restores the state and
+/// stepping forward again knows which change it is redoing. The oldest snapshots are forgotten once they take more
+/// than `limit` bytes.
+#[derive(Debug, Default)]
+pub struct History {
+  limit: usize,
+  bytes: usize,
+  past: VecDeque<(Snapshot, Change)>,
+  future: Vec<(Snapshot, Change)>,
+}
+
+impl History {
+  pub fn new(limit: usize) -> Self {
+    Self { limit, ..Self::default() }
+  }
+
+  /// Remembers the current state before `change` is made to it, forgetting anything that was undone.
+  pub fn record(&mut self, current: Snapshot, change: Change) {
+    for (snapshot, _) in self.future.drain(..) {
+      self.bytes -= snapshot.bytes();
+    }
+    self.bytes += current.bytes();
+    self.past.push_back((current, change));
+    while self.bytes > self.limit {
+      let Some((snapshot, _)) = self.past.pop_front() else {
+        break;
+      };
+      self.bytes -= snapshot.bytes();
+    }
+  }
+
+  /// Goes back one change, returning the state before it.
+  pub fn back(&mut self, current: Snapshot) -> Option<(Snapshot, Change)> {
+    let (snapshot, change) = self.past.pop_back()?;
+    self.bytes = self.bytes + current.bytes() - snapshot.bytes();
+    self.future.push((current, change));
+    Some((snapshot, change))
+  }
+
+  /// Goes forward one change that was gone back over, returning the state after it.
+  pub fn forward(&mut self, current: Snapshot) -> Option<(Snapshot, Change)> {
+    let (snapshot, change) = self.future.pop()?;
+    self.bytes = self.bytes + current.bytes() - snapshot.bytes();
+    self.past.push_back((current, change));
+    Some((snapshot, change))
+  }
+
+  /// Goes back to the state before the last edit, along with any generations since.
+  pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+    let (mut current, mut change) = self.back(current)?;
+    while change != Change::Edit && !self.past.is_empty() {
+      (current, change) = self.back(current)?;
+    }
+    Some(current)
+  }
+
+  /// Redoes the last undone edit, along with the generations that followed it.
+  pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+    let (mut current, _) = self.forward(current)?;
+    while self.future.last().is_some_and(|(_, change)| *change == Change::Generation) {
+      (current, _) = self.forward(current)?;
+    }
+    Some(current)
+  }
+
+  pub fn can_go_forward(&self) -> bool {
+    !self.future.is_empty()
+  }
+
+  pub fn clear(&mut self) {
+    self.past.clear();
+    self.future.clear();
+    self.bytes = 0;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn snapshot(generation: usize) -> Snapshot {
+    Snapshot::new(&[vec![Cell::from(generation % 2 == 1)]], generation)
+  }
+
+  #[test]
+  fn test_undo_redo() {
+    let mut history = History::new(usize::MAX);
+    history.record(snapshot(0), Change::Generation);
+    history.record(snapshot(1), Change::Edit);
+    history.record(snapshot(2), Change::Generation);
+    history.record(snapshot(3), Change::Generation);
+    assert_eq!(history.undo(snapshot(4)), Some(snapshot(1)));
+    assert_eq!(history.redo(snapshot(1)), Some(snapshot(4)));
+    assert_eq!(history.back(snapshot(4)), Some((snapshot(3), Change::Generation)));
+    assert_eq!(history.forward(snapshot(3)), Some((snapshot(4), Change::Generation)));
+    assert_eq!(history.forward(snapshot(4)), None);
+  }
+
+  #[test]
+  fn test_record_forgets_future() {
+    let mut history = History::new(usize::MAX);
+    history.record(snapshot(0), Change::Edit);
+    history.back(snapshot(1));
+    history.record(snapshot(0), Change::Generation);
+    assert_eq!(history.redo(snapshot(1)), None);
+  }
+
+  #[test]
+  fn test_limit() {
+    let mut history = History::new(snapshot(0).bytes() * 2);
+    for generation in 0..5 {
+      history.record(snapshot(generation), Change::Generation);
+    }
+    assert_eq!(history.back(snapshot(5)).map(|(s, _)| s.generation), Some(4));
+    assert_eq!(history.back(snapshot(4)).map(|(s, _)| s.generation), Some(3));
+    assert_eq!(history.back(snapshot(3)), None);
+  }
+}
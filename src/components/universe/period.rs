@@ -0,0 +1,221 @@
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+  fmt,
+  hash::{Hash, Hasher},
+};
+
+use serde::Serialize;
+
+use super::selection::Selection;
+use crate::parsers::Cell;
+
+/// Number of generations that are remembered, which is also the longest period that can be detected.
+const MAX_PERIOD: usize = 1000;
+
+/// How the universe, or a region of it, repeats itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Periodicity {
+  Extinct,
+  StillLife,
+  Oscillator {
+    period: usize,
+  },
+  /// Repeats itself `period` generations later, moved by `(dx, dy)` cells.
+  Spaceship {
+    period: usize,
+    dx: isize,
+    dy: isize,
+  },
+}
+
+impl fmt::Display for Periodicity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match *self {
+      Periodicity::Extinct => write!(f, "died out"),
+      Periodicity::StillLife => write!(f, "still life"),
+      Periodicity::Oscillator { period } => write!(f, "period {period} oscillator"),
+      Periodicity::Spaceship { period, dx, dy } => {
+        write!(f, "spaceship with period {period} and displacement ({dx}, {dy}), speed {}", speed(period, dx, dy))
+      },
+    }
+  }
+}
+
+/// Returns the speed of a spaceship in the usual notation, such as `c/4` for a glider or `2c/5` for a copperhead.
+fn speed(period: usize, dx: isize, dy: isize) -> String {
+  let distance = dx.unsigned_abs().max(dy.unsigned_abs());
+  let divisor = gcd(distance, period);
+  let (distance, period) = (distance / divisor, period / divisor);
+  let c = if distance == 1 { "c".to_string() } else { format!("{distance}c") };
+  if period == 1 {
+    c
+  } else {
+    format!("{c}/{period}")
+  }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+/// Finds where the live cells start along one axis of a torus: just after the widest run of empty lines, so that
+/// patterns that cross the edge are not split.
+fn start(mut occupied: Vec<usize>, size: usize) -> usize {
+  occupied.sort_unstable();
+  occupied.dedup();
+  let mut best = (0, occupied.first().copied().unwrap_or_default());
+  for (i, &line) in occupied.iter().enumerate() {
+    let next = occupied[(i + 1) % occupied.len()];
+    let gap = (next + size - line - 1) % size;
+    if gap > best.0 {
+      best = (gap, next);
+    }
+  }
+  best.1
+}
+
+/// Wraps a difference between two positions on a torus to the shortest way around.
+fn wrap(d: isize, size: usize) -> isize {
+  let size = size as isize;
+  let d = d.rem_euclid(size);
+  if d > size / 2 {
+    d - size
+  } else {
+    d
+  }
+}
+
+/// Detects when the universe, or a region of it, repeats itself, by hashing the live cells of every generation
+/// relative to where they start.
+#[derive(Debug, Default)]
+pub struct PeriodDetector {
+  region: Option<Selection>,
+  /// Generation and start of each hashed state that is remembered.
+  seen: HashMap<u64, (usize, (usize, usize))>,
+  order: VecDeque<u64>,
+  found: Option<Periodicity>,
+}
+
+impl PeriodDetector {
+  /// Forgets everything, for when the cells change other than by advancing.
+  pub fn clear(&mut self) {
+    self.seen.clear();
+    self.order.clear();
+    self.found = None;
+  }
+
+  /// Returns how the region repeats itself, if that was found for it.
+  pub fn periodicity(&self, region: Option<Selection>) -> Option<Periodicity> {
+    self.found.filter(|_| self.region == region)
+  }
+
+  /// Records a generation of the cells in the region, or all of them when there is none. Returns how they repeat
+  /// themselves when that is first found.
+  pub fn record(&mut self, cells: &[Vec<Cell>], generation: usize, region: Option<Selection>) -> Option<Periodicity> {
+    if self.region != region {
+      self.clear();
+      self.region = region;
+    }
+    if self.found.is_some() {
+      return None;
+    }
+    let (x0, y0, width, height) = match region {
+      Some(selection) => {
+        let (x0, y0, _, _) = selection.bounds();
+        let (width, height) = selection.size();
+        (x0, y0, width, height)
+      },
+      None => (0, 0, cells.first().map_or(0, |row| row.len()), cells.len()),
+    };
+    let live: Vec<(usize, usize)> = (0..height)
+      .flat_map(|y| (0..width).map(move |x| (x, y)))
+      .filter(|&(x, y)| {
+        cells.get(y0 + y).and_then(|row| row.get(x0 + x)).is_some_and(|cell| matches!(cell, Cell::Alive(_)))
+      })
+      .collect();
+    if live.is_empty() {
+      self.found = Some(Periodicity::Extinct);
+      return self.found;
+    }
+
+    let origin = (start(live.iter().map(|p| p.0).collect(), width), start(live.iter().map(|p| p.1).collect(), height));
+    let mut normalized: Vec<(usize, usize)> =
+      live.iter().map(|&(x, y)| ((x + width - origin.0) % width, (y + height - origin.1) % height)).collect();
+    normalized.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    normalized.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    if let Some(&(previous, (px, py))) = self.seen.get(&hash) {
+      let period = generation - previous;
+      let dx = wrap(origin.0 as isize - px as isize, width);
+      let dy = wrap(origin.1 as isize - py as isize, height);
+      self.found = Some(match (period, dx, dy) {
+        (1, 0, 0) => Periodicity::StillLife,
+        (period, 0, 0) => Periodicity::Oscillator { period },
+        (period, dx, dy) => Periodicity::Spaceship { period, dx, dy },
+      });
+      return self.found;
+    }
+    self.seen.insert(hash, (generation, origin));
+    self.order.push_back(hash);
+    if self.order.len() > MAX_PERIOD {
+      if let Some(oldest) = self.order.pop_front() {
+        self.seen.remove(&oldest);
+      }
+    }
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn cells(live: &[(usize, usize)]) -> Vec<Vec<Cell>> {
+    let mut cells = vec![vec![Cell::EMPTY; 8]; 8];
+    for &(x, y) in live {
+      cells[y][x] = Cell::Alive(0);
+    }
+    cells
+  }
+
+  #[test]
+  fn test_record() {
+    let mut detector = PeriodDetector::default();
+    let horizontal = cells(&[(3, 4), (4, 4), (5, 4)]);
+    let vertical = cells(&[(4, 3), (4, 4), (4, 5)]);
+    assert_eq!(detector.record(&horizontal, 0, None), None);
+    assert_eq!(detector.record(&vertical, 1, None), None);
+    assert_eq!(detector.record(&horizontal, 2, None), Some(Periodicity::Oscillator { period: 2 }));
+
+    // Moving across the edge of the torus does not split the pattern.
+    detector.clear();
+    assert_eq!(detector.record(&cells(&[(6, 0), (6, 1)]), 0, None), None);
+    let found = detector.record(&cells(&[(7, 7), (7, 0)]), 1, None);
+    assert_eq!(found, Some(Periodicity::Spaceship { period: 1, dx: 1, dy: -1 }));
+
+    // Only the cells in the region count, so a block next to a blinker is still.
+    let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    let region = Some(Selection::new((0, 0), (2, 2)));
+    assert_eq!(detector.record(&cells(&[&block[..], &[(3, 4), (4, 4), (5, 4)]].concat()), 0, region), None);
+    let found = detector.record(&cells(&[&block[..], &[(4, 3), (4, 4), (4, 5)]].concat()), 1, region);
+    assert_eq!(found, Some(Periodicity::StillLife));
+    assert_eq!(detector.periodicity(None), None);
+  }
+
+  #[test]
+  fn test_display() {
+    assert_eq!(Periodicity::Oscillator { period: 3 }.to_string(), "period 3 oscillator");
+    let glider = Periodicity::Spaceship { period: 4, dx: 1, dy: 1 };
+    assert_eq!(glider.to_string(), "spaceship with period 4 and displacement (1, 1), speed c/4");
+    assert_eq!(speed(4, -2, 0), "c/2");
+    assert_eq!(speed(10, 0, 4), "2c/5");
+  }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use clap::ValueEnum;
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+  camera::Camera,
+  heatmap::{heat_color, Heatmap},
+  selection::Selection,
+  theme::{to_ansi16, Theme},
+};
+use crate::parsers::Cell;
+
+/// Bits of the braille dots in a character, indexed by row and then column.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// How the pixels of the viewport are laid out in terminal characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum RenderMode {
+  /// Upper and lower half blocks, with 1x2 pixels per character.
+  #[default]
+  HalfBlock,
+  /// Braille patterns, with 2x4 pixels per character.
+  Braille,
+  /// One plain ASCII character per pixel, for terminals that cannot draw anything else.
+  Ascii,
+}
+
+impl RenderMode {
+  /// Guesses from `TERM` whether the terminal can draw block characters.
+  pub fn detect() -> Self {
+    match std::env::var("TERM").as_deref() {
+      Err(_) | Ok("" | "dumb" | "vt52" | "vt100" | "vt102" | "vt220" | "ansi") => RenderMode::Ascii,
+      Ok(_) => RenderMode::HalfBlock,
+    }
+  }
+
+  /// Number of pixels along the width and height of a character.
+  pub fn pixels_per_char(&self) -> (usize, usize) {
+    match self {
+      RenderMode::HalfBlock => (1, 2),
+      RenderMode::Braille => (2, 4),
+      RenderMode::Ascii => (1, 1),
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      RenderMode::HalfBlock => RenderMode::Braille,
+      RenderMode::Braille => RenderMode::Ascii,
+      RenderMode::Ascii => RenderMode::HalfBlock,
+    }
+  }
+}
+
+/// Which colors the terminal can show.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+pub enum ColorSupport {
+  /// 24-bit RGB colors.
+  #[default]
+  TrueColor,
+  /// The 16 standard ANSI colors.
+  Ansi16,
+  /// No colors at all.
+  None,
+}
+
+impl ColorSupport {
+  /// Guesses the color support from the `NO_COLOR`, `COLORTERM` and `TERM` environment variables.
+  pub fn detect() -> Self {
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+      return ColorSupport::None;
+    }
+    if matches!(std::env::var("COLORTERM").as_deref(), Ok("truecolor" | "24bit")) {
+      return ColorSupport::TrueColor;
+    }
+    match std::env::var("TERM").as_deref() {
+      Err(_) | Ok("" | "dumb") => ColorSupport::None,
+      Ok(term) if term.ends_with("-direct") => ColorSupport::TrueColor,
+      Ok(_) => ColorSupport::Ansi16,
+    }
+  }
+}
+
+/// Extra information drawn on top of the live cells.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Overlay {
+  /// Only the cells that died in the last generation, in the theme's dying color.
+  #[default]
+  None,
+  /// Dead cells fade out over a number of generations.
+  Trails,
+  /// Cells are colored by how often they have been alive recently.
+  Heatmap,
+}
+
+impl Overlay {
+  pub fn next(&self) -> Self {
+    match self {
+      Overlay::None => Overlay::Trails,
+      Overlay::Trails => Overlay::Heatmap,
+      Overlay::Heatmap => Overlay::None,
+    }
+  }
+}
+
+/// Draws the part of the universe that the camera is looking at.
+pub struct View<'a> {
+  pub cells: &'a [Vec<Cell>],
+  pub camera: Camera,
+  pub mode: RenderMode,
+  pub colors: ColorSupport,
+  pub theme: Theme,
+  pub overlay: Overlay,
+  /// Number of generations that trails take to fade out.
+  pub trails: usize,
+  pub heatmap: &'a Heatmap,
+  /// Characters for live and dead cells in ASCII mode.
+  pub glyphs: (char, char),
+  /// Rectangle of cells that is tinted.
+  pub selection: Option<Selection>,
+  /// Cells of a pattern that is about to be pasted, which are drawn faintly.
+  pub preview: &'a HashSet<(usize, usize)>,
+}
+
+/// What is shown in a pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pixel {
+  Empty,
+  /// A dead cell that is colored by an overlay.
+  Trace(Color),
+  Alive(Color),
+}
+
+impl Pixel {
+  fn color(self) -> Option<Color> {
+    match self {
+      Pixel::Empty => None,
+      Pixel::Trace(color) | Pixel::Alive(color) => Some(color),
+    }
+  }
+}
+
+impl View<'_> {
+  fn size(&self) -> (usize, usize) {
+    (self.cells.first().map_or(0, |row| row.len()), self.cells.len())
+  }
+
+  /// Draws the paste preview and the selection over the cells.
+  fn pixel(&self, px: usize, py: usize) -> Pixel {
+    let pixel = self.cell_pixel(px, py);
+    let (width, height) = self.size();
+    let Some((x, y)) = self.camera.cell_at(px, py, width, height) else {
+      return pixel;
+    };
+    match pixel {
+      _ if self.preview.contains(&(x, y)) => Pixel::Alive(self.color(self.theme.shade(self.theme.young, 0.5))),
+      Pixel::Empty if self.selection.is_some_and(|s| s.contains(x, y)) && self.colors != ColorSupport::None => {
+        Pixel::Trace(self.color(self.theme.shade(self.theme.young, 0.2)))
+      },
+      pixel => pixel,
+    }
+  }
+
+  fn cell_pixel(&self, px: usize, py: usize) -> Pixel {
+    let (width, height) = self.size();
+    let (ox, oy, k) = self.camera.block_at(px, py);
+    if ox >= width || oy >= height {
+      return Pixel::Empty;
+    }
+    if k == 1 {
+      let (x, y) = ((self.camera.x + ox) % width, (self.camera.y + oy) % height);
+      return match self.cells[y][x] {
+        cell if self.overlay == Overlay::Heatmap => self.heat(matches!(cell, Cell::Alive(_)), self.heatmap.heat(x, y)),
+        Cell::Alive(age) => Pixel::Alive(self.color(self.theme.alive(age))),
+        Cell::Dead(age) => self.trace(age),
+      };
+    }
+    // Summarize the block, showing how many of its cells are alive by how bright the pixel is.
+    let (mut alive, mut age, mut total, mut dead, mut heat) = (0, 0.0, 0, usize::MAX, 0.0_f64);
+    for y in (oy..oy + k).take_while(|&y| y < height) {
+      let y = (self.camera.y + y) % height;
+      for x in (ox..ox + k).take_while(|&x| x < width) {
+        let x = (self.camera.x + x) % width;
+        match self.cells[y][x] {
+          Cell::Alive(a) => {
+            alive += 1;
+            age += a.min(self.theme.gradient) as f64 / self.theme.gradient.max(1) as f64;
+          },
+          Cell::Dead(d) => dead = dead.min(d),
+        }
+        if self.overlay == Overlay::Heatmap {
+          heat = heat.max(self.heatmap.heat(x, y));
+        }
+        total += 1;
+      }
+    }
+    if self.overlay == Overlay::Heatmap {
+      return self.heat(alive > 0, heat);
+    }
+    if alive == 0 {
+      return self.trace(dead);
+    }
+    let color = self.theme.shade(self.theme.blend(age / alive as f64), 0.25 + 0.75 * alive as f64 / total as f64);
+    Pixel::Alive(self.color(color))
+  }
+
+  /// Draws a cell that has been dead for `age` generations.
+  fn trace(&self, age: usize) -> Pixel {
+    let color = match self.overlay {
+      Overlay::Trails if age < self.trails => {
+        let color = self.theme.dying.unwrap_or(self.theme.old);
+        Some(self.theme.shade(color, 1.0 - age as f64 / self.trails as f64))
+      },
+      _ if age == 0 => self.theme.dying,
+      _ => None,
+    };
+    match color {
+      Some(color) if self.colors != ColorSupport::None => Pixel::Trace(self.color(color)),
+      _ => Pixel::Empty,
+    }
+  }
+
+  /// Draws a cell that was alive for `heat` of the generations in the heatmap.
+  fn heat(&self, alive: bool, heat: f64) -> Pixel {
+    let color = self.color(heat_color(heat));
+    if alive {
+      Pixel::Alive(color)
+    } else if heat > 0.0 && self.colors != ColorSupport::None {
+      Pixel::Trace(color)
+    } else {
+      Pixel::Empty
+    }
+  }
+
+  /// Picks the closest color the terminal can show.
+  fn color(&self, color: Color) -> Color {
+    match self.colors {
+      ColorSupport::TrueColor => color,
+      ColorSupport::Ansi16 => to_ansi16(color),
+      ColorSupport::None => Color::Reset,
+    }
+  }
+
+  fn style(&self, fg: Option<Color>) -> Style {
+    Style::default().fg(fg.unwrap_or(Color::Reset)).bg(self.color(self.theme.background))
+  }
+
+  fn half_block(&self, col: usize, row: usize) -> (char, Style) {
+    let (top, bottom) = (self.pixel(col, row * 2).color(), self.pixel(col, row * 2 + 1).color());
+    match (top, bottom) {
+      // Without colors the background cannot tell the two halves apart, so both are drawn in the foreground.
+      (Some(_), Some(_)) if self.colors == ColorSupport::None => ('█', Style::default()),
+      (Some(top), Some(bottom)) => ('▀', Style::default().fg(top).bg(bottom)),
+      (Some(top), None) => ('▀', self.style(Some(top))),
+      (None, Some(bottom)) => ('▄', self.style(Some(bottom))),
+      (None, None) => (' ', self.style(None)),
+    }
+  }
+
+  /// Draws a character as braille dots. A character can only have one color, so it takes the color of most of its
+  /// dots.
+  fn braille(&self, col: usize, row: usize) -> (char, Style) {
+    let mut bits = 0;
+    let mut colors: Vec<(Color, usize)> = vec![];
+    for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+      for (dx, bit) in dots.iter().enumerate() {
+        if let Some(color) = self.pixel(col * 2 + dx, row * 4 + dy).color() {
+          bits |= bit;
+          match colors.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, n)) => *n += 1,
+            None => colors.push((color, 1)),
+          }
+        }
+      }
+    }
+    let color = colors.iter().rev().max_by_key(|(_, n)| *n).map(|(c, _)| *c);
+    let ch = if bits == 0 { ' ' } else { char::from_u32(0x2800 + bits).unwrap_or(' ') };
+    (ch, self.style(color))
+  }
+
+  /// Draws `width` by `height` characters in ASCII mode as plain text, one line per row.
+  pub fn to_text(&self, width: usize, height: usize) -> String {
+    let mut text = String::with_capacity((width + 1) * height);
+    for row in 0..height {
+      text.extend((0..width).map(|col| self.ascii(col, row).0));
+      text.push('\n');
+    }
+    text
+  }
+
+  fn ascii(&self, col: usize, row: usize) -> (char, Style) {
+    match self.pixel(col, row) {
+      Pixel::Alive(color) => (self.glyphs.0, self.style(Some(color))),
+      Pixel::Trace(color) => (self.glyphs.1, self.style(Some(color))),
+      Pixel::Empty => (self.glyphs.1, self.style(None)),
+    }
+  }
+}
+
+impl Widget for View<'_> {
+  fn render(self, area: Rect, buf: &mut Buffer) {
+    for row in 0..area.height {
+      for col in 0..area.width {
+        let (ch, style) = match self.mode {
+          RenderMode::HalfBlock => self.half_block(col as usize, row as usize),
+          RenderMode::Braille => self.braille(col as usize, row as usize),
+          RenderMode::Ascii => self.ascii(col as usize, row as usize),
+        };
+        let cell = buf.get_mut(area.left() + col, area.top() + row);
+        cell.set_char(ch);
+        cell.set_style(style);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_to_text() {
+    let mut cells = vec![vec![Cell::Dead(0); 4]; 3];
+    cells[0][1] = Cell::Alive(0);
+    cells[1][2] = Cell::Alive(3);
+    cells[2][0] = Cell::Alive(1);
+    let camera = Camera { x: 1, ..Camera::default() };
+    let view = View {
+      cells: &cells,
+      camera,
+      mode: RenderMode::Ascii,
+      colors: ColorSupport::None,
+      theme: Theme::default(),
+      overlay: Overlay::None,
+      trails: 0,
+      heatmap: &Heatmap::default(),
+      glyphs: ('O', '.'),
+      selection: None,
+      preview: &HashSet::new(),
+    };
+    assert_eq!(view.to_text(4, 3), "O...\n.O..\n...O\n");
+  }
+}
@@ -0,0 +1,49 @@
+/// A rectangle of cells between two corners, which can be in any order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+  pub from: (usize, usize),
+  pub to: (usize, usize),
+}
+
+impl Selection {
+  pub fn new(from: (usize, usize), to: (usize, usize)) -> Self {
+    Self { from, to }
+  }
+
+  /// Returns the top left and bottom right corners, inclusive.
+  pub fn bounds(&self) -> (usize, usize, usize, usize) {
+    (self.from.0.min(self.to.0), self.from.1.min(self.to.1), self.from.0.max(self.to.0), self.from.1.max(self.to.1))
+  }
+
+  pub fn size(&self) -> (usize, usize) {
+    let (x0, y0, x1, y1) = self.bounds();
+    (x1 - x0 + 1, y1 - y0 + 1)
+  }
+
+  pub fn contains(&self, x: usize, y: usize) -> bool {
+    let (x0, y0, x1, y1) = self.bounds();
+    (x0..=x1).contains(&x) && (y0..=y1).contains(&y)
+  }
+
+  /// Iterates over the selected cells as `(x, y)`, row by row.
+  pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+    let (x0, y0, x1, y1) = self.bounds();
+    (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_bounds() {
+    let selection = Selection::new((5, 1), (3, 2));
+    assert_eq!(selection.bounds(), (3, 1, 5, 2));
+    assert_eq!(selection.size(), (3, 2));
+    assert!(selection.contains(4, 2) && !selection.contains(6, 1));
+    assert_eq!(selection.cells().count(), 6);
+  }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Style};
+
+/// Colors that cells are drawn with.
+///
+/// A theme can be customized with the `young`, `old`, `dying` and `background` keys of the `Home` styles in the
+/// config, which take the foreground color of the style (or the background color for `background`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+  pub name: &'static str,
+  /// Color of cells that were just born.
+  pub young: Color,
+  /// Color of cells that have been alive for `gradient` generations or more.
+  pub old: Color,
+  /// Color of cells that died in the last generation, or `None` to leave them empty.
+  pub dying: Option<Color>,
+  pub background: Color,
+  /// Number of generations that it takes a cell to fade from `young` to `old`.
+  pub gradient: usize,
+}
+
+pub const THEMES: &[Theme] = &[
+  Theme {
+    name: "classic",
+    young: Color::Rgb(255, 213, 57),
+    old: Color::Rgb(202, 32, 77),
+    dying: None,
+    background: Color::Reset,
+    gradient: 1,
+  },
+  Theme {
+    name: "ember",
+    young: Color::Rgb(255, 250, 200),
+    old: Color::Rgb(190, 40, 0),
+    dying: Some(Color::Rgb(70, 20, 10)),
+    background: Color::Reset,
+    gradient: 24,
+  },
+  Theme {
+    name: "ocean",
+    young: Color::Rgb(210, 255, 255),
+    old: Color::Rgb(0, 90, 210),
+    dying: Some(Color::Rgb(0, 40, 80)),
+    background: Color::Rgb(0, 10, 25),
+    gradient: 24,
+  },
+  Theme {
+    name: "forest",
+    young: Color::Rgb(210, 255, 120),
+    old: Color::Rgb(20, 120, 40),
+    dying: Some(Color::Rgb(70, 50, 20)),
+    background: Color::Reset,
+    gradient: 32,
+  },
+  Theme {
+    name: "mono",
+    young: Color::Rgb(255, 255, 255),
+    old: Color::Rgb(140, 140, 140),
+    dying: None,
+    background: Color::Reset,
+    gradient: 16,
+  },
+  Theme {
+    name: "paper",
+    young: Color::Rgb(0, 0, 0),
+    old: Color::Rgb(70, 90, 140),
+    dying: Some(Color::Rgb(220, 215, 200)),
+    background: Color::Rgb(250, 246, 235),
+    gradient: 16,
+  },
+];
+
+impl Default for Theme {
+  fn default() -> Self {
+    THEMES[0]
+  }
+}
+
+impl Theme {
+  pub fn get(name: &str) -> Option<Self> {
+    THEMES.iter().find(|theme| theme.name.eq_ignore_ascii_case(name)).copied()
+  }
+
+  /// Applies the colors set in `styles` on top of this theme, or returns `None` if none of them are set.
+  pub fn customize(self, styles: &HashMap<String, Style>) -> Option<Self> {
+    let fg = |key: &str| styles.get(key).and_then(|style| style.fg);
+    let background = styles.get("background").and_then(|style| style.bg.or(style.fg));
+    if fg("young").is_none() && fg("old").is_none() && fg("dying").is_none() && background.is_none() {
+      return None;
+    }
+    Some(Self {
+      name: "custom",
+      young: fg("young").unwrap_or(self.young),
+      old: fg("old").unwrap_or(self.old),
+      dying: fg("dying").or(self.dying),
+      background: background.unwrap_or(self.background),
+      gradient: self.gradient,
+    })
+  }
+
+  /// Color of a cell that has been alive for `age` generations.
+  pub fn alive(&self, age: usize) -> Color {
+    self.blend(age.min(self.gradient) as f64 / self.gradient.max(1) as f64)
+  }
+
+  /// Mixes `young` and `old`, from all young at 0 to all old at 1.
+  pub fn blend(&self, t: f64) -> Color {
+    mix(self.young, self.old, t)
+  }
+
+  /// Fades `color` into the background, from invisible at 0 to unchanged at 1.
+  pub fn shade(&self, color: Color, intensity: f64) -> Color {
+    let background = match self.background {
+      Color::Rgb(..) => self.background,
+      _ => Color::Rgb(0, 0, 0),
+    };
+    mix(background, color, intensity)
+  }
+}
+
+/// Interpolates between two RGB colors. Other colors cannot be mixed, so the closer one is picked.
+pub(super) fn mix(from: Color, to: Color, t: f64) -> Color {
+  match (from, to) {
+    (Color::Rgb(r0, g0, b0), Color::Rgb(r1, g1, b1)) => {
+      let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t.clamp(0.0, 1.0)).round() as u8;
+      Color::Rgb(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1))
+    },
+    _ if t < 0.5 => from,
+    _ => to,
+  }
+}
+
+/// Picks the closest of the 16 standard ANSI colors to an RGB color.
+pub fn to_ansi16(color: Color) -> Color {
+  const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (170, 0, 0)),
+    (Color::Green, (0, 170, 0)),
+    (Color::Yellow, (170, 85, 0)),
+    (Color::Blue, (0, 0, 170)),
+    (Color::Magenta, (170, 0, 170)),
+    (Color::Cyan, (0, 170, 170)),
+    (Color::Gray, (170, 170, 170)),
+    (Color::DarkGray, (85, 85, 85)),
+    (Color::LightRed, (255, 85, 85)),
+    (Color::LightGreen, (85, 255, 85)),
+    (Color::LightYellow, (255, 255, 85)),
+    (Color::LightBlue, (85, 85, 255)),
+    (Color::LightMagenta, (255, 85, 255)),
+    (Color::LightCyan, (85, 255, 255)),
+    (Color::White, (255, 255, 255)),
+  ];
+  let Color::Rgb(r, g, b) = color else {
+    return color;
+  };
+  let distance = |(r1, g1, b1): (u8, u8, u8)| {
+    (r as i32 - r1 as i32).pow(2) + (g as i32 - g1 as i32).pow(2) + (b as i32 - b1 as i32).pow(2)
+  };
+  ANSI16.iter().min_by_key(|(_, rgb)| distance(*rgb)).map_or(color, |(c, _)| *c)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_gradient() {
+    let theme = Theme::get("mono").unwrap();
+    assert_eq!(theme.alive(0), theme.young);
+    assert_eq!(theme.alive(8), Color::Rgb(198, 198, 198));
+    assert_eq!(theme.alive(100), theme.old);
+    assert_eq!(Theme::default().alive(1), Theme::default().old);
+  }
+
+  #[test]
+  fn test_customize() {
+    let styles = HashMap::from([
+      ("young".to_string(), Style::default().fg(Color::Indexed(1))),
+      ("background".to_string(), Style::default().bg(Color::Indexed(0))),
+    ]);
+    let theme = Theme::default().customize(&styles).unwrap();
+    assert_eq!(theme.young, Color::Indexed(1));
+    assert_eq!(theme.old, Theme::default().old);
+    assert_eq!(theme.background, Color::Indexed(0));
+    assert_eq!(Theme::default().customize(&HashMap::new()), None);
+  }
+
+  #[test]
+  fn test_to_ansi16() {
+    assert_eq!(to_ansi16(Color::Rgb(255, 213, 57)), Color::LightYellow);
+    assert_eq!(to_ansi16(Color::Rgb(202, 32, 77)), Color::LightRed);
+    assert_eq!(to_ansi16(Color::Rgb(0, 40, 80)), Color::Black);
+    assert_eq!(to_ansi16(Color::Indexed(42)), Color::Indexed(42));
+  }
+}
@@ -0,0 +1,196 @@
+use std::collections::{HashSet, VecDeque};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::Cell;
+
+type Point = (isize, isize);
+
+/// What dragging with the left mouse button draws.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tool {
+  /// Draws wherever the mouse goes.
+  #[default]
+  Freehand,
+  Line,
+  Rectangle,
+  FilledRectangle,
+  /// Draws the ellipse that fits in the rectangle that was dragged.
+  Ellipse,
+  /// Flips the region of dead or live cells that was clicked.
+  Fill,
+}
+
+impl Tool {
+  pub const ALL: [Tool; 6] =
+    [Tool::Freehand, Tool::Line, Tool::Rectangle, Tool::FilledRectangle, Tool::Ellipse, Tool::Fill];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Tool::Freehand => "pen",
+      Tool::Line => "line",
+      Tool::Rectangle => "rectangle",
+      Tool::FilledRectangle => "filled rectangle",
+      Tool::Ellipse => "ellipse",
+      Tool::Fill => "fill",
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    let i = Self::ALL.iter().position(|tool| tool == self).unwrap_or_default();
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  /// Returns the points of the shape dragged from `from` to `to`, or nothing for tools that do not draw shapes.
+  pub fn shape(&self, from: Point, to: Point) -> Vec<Point> {
+    match self {
+      Tool::Line => line(from, to),
+      Tool::Rectangle => rectangle(from, to, false),
+      Tool::FilledRectangle => rectangle(from, to, true),
+      Tool::Ellipse => ellipse(from, to),
+      Tool::Freehand | Tool::Fill => vec![],
+    }
+  }
+}
+
+/// Returns the points of a line with Bresenham's algorithm, including both ends.
+pub fn line((mut x0, mut y0): Point, (x1, y1): Point) -> Vec<Point> {
+  let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+  let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+  let mut err = dx + dy;
+  let mut points = vec![];
+  loop {
+    points.push((x0, y0));
+    if (x0, y0) == (x1, y1) {
+      return points;
+    }
+    let e2 = 2 * err;
+    if e2 >= dy {
+      err += dy;
+      x0 += sx;
+    }
+    if e2 <= dx {
+      err += dx;
+      y0 += sy;
+    }
+  }
+}
+
+pub fn rectangle((x0, y0): Point, (x1, y1): Point, filled: bool) -> Vec<Point> {
+  let (x0, x1) = (x0.min(x1), x0.max(x1));
+  let (y0, y1) = (y0.min(y1), y0.max(y1));
+  (y0..=y1)
+    .cartesian_product(x0..=x1)
+    .filter(|&(y, x)| filled || x == x0 || x == x1 || y == y0 || y == y1)
+    .map(|(y, x)| (x, y))
+    .collect()
+}
+
+/// Returns the points of the ellipse that fits in the rectangle between two corners, with Zingl's variant of the
+/// midpoint algorithm, which also handles even widths and heights.
+pub fn ellipse((x0, y0): Point, (x1, y1): Point) -> Vec<Point> {
+  let (mut x0, mut x1) = (x0.min(x1), x0.max(x1));
+  let (top, bottom) = (y0.min(y1), y0.max(y1));
+  let (a, b) = (x1 - x0, bottom - top);
+  let b1 = b & 1;
+  let (mut dx, mut dy) = (4 * (1 - a) * b * b, 4 * (b1 + 1) * a * a);
+  let mut err = dx + dy + b1 * a * a;
+  let mut y0 = top + (b + 1) / 2;
+  let mut y1 = y0 - b1;
+  let (a8, b8) = (8 * a * a, 8 * b * b);
+  let mut points = vec![];
+  loop {
+    points.extend([(x1, y0), (x0, y0), (x0, y1), (x1, y1)]);
+    let e2 = 2 * err;
+    if e2 <= dy {
+      y0 += 1;
+      y1 -= 1;
+      dy += a8;
+      err += dy;
+    }
+    if e2 >= dx || 2 * err > dy {
+      x0 += 1;
+      x1 -= 1;
+      dx += b8;
+      err += dx;
+    }
+    if x0 > x1 {
+      break;
+    }
+  }
+  // Flat ellipses stop early, so finish their tips.
+  while y0 - y1 < b {
+    points.extend([(x0 - 1, y0), (x1 + 1, y0), (x0 - 1, y1), (x1 + 1, y1)]);
+    y0 += 1;
+    y1 -= 1;
+  }
+  points.into_iter().unique().collect()
+}
+
+/// Returns the cells connected to `(x, y)` through their sides that are alive if it is alive, or dead if it is dead,
+/// wrapping around the edges.
+pub fn flood_fill(cells: &[Vec<Cell>], (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+  let (width, height) = (cells.first().map_or(0, |row| row.len()), cells.len());
+  if x >= width || y >= height {
+    return vec![];
+  }
+  let alive = |(x, y): (usize, usize)| matches!(cells[y][x], Cell::Alive(_));
+  let state = alive((x, y));
+  let mut seen = HashSet::from([(x, y)]);
+  let mut queue = VecDeque::from([(x, y)]);
+  while let Some((x, y)) = queue.pop_front() {
+    let neighbors =
+      [((x + width - 1) % width, y), ((x + 1) % width, y), (x, (y + height - 1) % height), (x, (y + 1) % height)];
+    for neighbor in neighbors {
+      if alive(neighbor) == state && seen.insert(neighbor) {
+        queue.push_back(neighbor);
+      }
+    }
+  }
+  seen.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_line() {
+    assert_eq!(line((0, 0), (4, 2)), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+    assert_eq!(line((1, 3), (1, 1)), vec![(1, 3), (1, 2), (1, 1)]);
+  }
+
+  #[test]
+  fn test_rectangle() {
+    assert_eq!(rectangle((2, 2), (0, 0), false).len(), 8);
+    assert_eq!(rectangle((0, 0), (2, 2), true).len(), 9);
+  }
+
+  #[test]
+  fn test_ellipse() {
+    let mut circle = ellipse((0, 0), (4, 4));
+    circle.sort_unstable();
+    assert_eq!(circle.iter().map(|p| p.0).minmax().into_option(), Some((0, 4)));
+    assert_eq!(circle.iter().map(|p| p.1).minmax().into_option(), Some((0, 4)));
+    // Mirrored horizontally, the circle is the same.
+    let mut mirrored: Vec<_> = circle.iter().map(|&(x, y)| (4 - x, y)).collect();
+    mirrored.sort_unstable();
+    assert_eq!(mirrored, circle);
+    assert!(!circle.contains(&(2, 2)));
+    assert_eq!(ellipse((0, 0), (5, 0)).len(), 6);
+  }
+
+  #[test]
+  fn test_flood_fill() {
+    let mut cells = vec![vec![Cell::EMPTY; 5]; 5];
+    for i in 0..5 {
+      cells[2][i] = Cell::Alive(0);
+    }
+    // The row of live cells splits the dead cells, but they are joined again around the top and bottom edges.
+    assert_eq!(flood_fill(&cells, (0, 0)).len(), 20);
+    assert_eq!(flood_fill(&cells, (3, 2)).len(), 5);
+  }
+}
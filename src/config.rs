@@ -6,7 +6,12 @@ use derive_deref::{Deref, DerefMut};
 use ratatui::style::{Color, Modifier, Style};
 use serde::{de::Deserializer, Deserialize};
 
-use crate::{action::Action, app::Mode};
+use crate::{
+  action::Action,
+  app::Mode,
+  components::universe::{ColorSupport, Overlay, RenderMode, ResizeAnchor},
+  soup::{self, Symmetry},
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -16,6 +21,80 @@ pub struct AppConfig {
   pub _data_dir: PathBuf,
   #[serde(default)]
   pub _config_dir: PathBuf,
+  #[serde(default)]
+  pub library_dir: Option<PathBuf>,
+  #[serde(default)]
+  pub resize_anchor: ResizeAnchor,
+  #[serde(default)]
+  pub world_size: Option<(usize, usize)>,
+  #[serde(default)]
+  pub seed: Option<u64>,
+  #[serde(default = "default_soup_density")]
+  pub soup_density: f64,
+  /// Columns and rows of the random soup, which goes in the middle of the view, instead of the whole universe.
+  #[serde(default)]
+  pub soup_size: Option<(usize, usize)>,
+  #[serde(default)]
+  pub symmetry: Symmetry,
+  #[serde(default)]
+  pub render_mode: Option<RenderMode>,
+  #[serde(default)]
+  pub colors: Option<ColorSupport>,
+  #[serde(default = "default_ascii_glyphs")]
+  pub ascii_glyphs: (char, char),
+  #[serde(default)]
+  pub speed: Option<f64>,
+  /// Megabytes of memory to keep snapshots of the universe in, for undo and stepping back.
+  #[serde(default = "default_history_megabytes")]
+  pub history_megabytes: usize,
+  #[serde(default = "default_eraser_size")]
+  pub eraser_size: usize,
+  #[serde(default)]
+  pub pause_on_period: bool,
+  #[serde(default = "default_census_distance")]
+  pub census_distance: usize,
+  #[serde(default)]
+  pub theme: Option<String>,
+  #[serde(default)]
+  pub age_gradient: Option<usize>,
+  #[serde(default)]
+  pub overlay: Overlay,
+  #[serde(default = "default_trail_length")]
+  pub trail_length: usize,
+  #[serde(default = "default_heatmap_window")]
+  pub heatmap_window: usize,
+  #[serde(default)]
+  pub dump_file: Option<PathBuf>,
+  #[serde(default)]
+  pub population_file: Option<PathBuf>,
+}
+
+fn default_ascii_glyphs() -> (char, char) {
+  ('#', '.')
+}
+
+fn default_history_megabytes() -> usize {
+  64
+}
+
+fn default_soup_density() -> f64 {
+  soup::DENSITY
+}
+
+fn default_census_distance() -> usize {
+  2
+}
+
+fn default_eraser_size() -> usize {
+  1
+}
+
+fn default_trail_length() -> usize {
+  8
+}
+
+fn default_heatmap_window() -> usize {
+  100
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -196,7 +275,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
       char = format!("f({c})");
       &char
     },
-    KeyCode::Char(c) if c == ' ' => "space",
+    KeyCode::Char(' ') => "space",
     KeyCode::Char(c) => {
       char = c.to_string();
       &char
@@ -328,6 +407,10 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
 fn parse_color(s: &str) -> Option<Color> {
   let s = s.trim_start();
   let s = s.trim_end();
+  if let Some(hex) = s.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii()) {
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+  }
   if s.contains("bright color") {
     let s = s.trim_start_matches("bright ");
     let c = s.trim_start_matches("color").parse::<u8>().unwrap_or_default();
@@ -428,6 +511,13 @@ mod tests {
     assert_eq!(color, Some(Color::Indexed(expected)));
   }
 
+  #[test]
+  fn test_parse_color_hex() {
+    assert_eq!(parse_color("#ffd539"), Some(Color::Rgb(255, 213, 57)));
+    assert_eq!(parse_style("#CA204D on #000000").bg, Some(Color::Rgb(0, 0, 0)));
+    assert_eq!(parse_color("#ffd5"), None);
+  }
+
   #[test]
   fn test_parse_color_unknown() {
     let color = parse_color("unknown");
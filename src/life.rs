@@ -0,0 +1,98 @@
+// Evolution of patterns on an unbounded plane, independent of the grid drawn by the `Universe` component.
+use std::collections::{HashMap, HashSet};
+
+pub type Coord = (isize, isize);
+
+/// How a pattern repeats itself: after `period` generations it is back to its original shape, shifted by
+/// `displacement`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Periodicity {
+  pub period: usize,
+  pub displacement: (isize, isize),
+}
+
+/// Advances a set of live cells by one generation using the B3/S23 rule.
+pub fn step(cells: &HashSet<Coord>) -> HashSet<Coord> {
+  let mut neighbors: HashMap<Coord, u8> = HashMap::with_capacity(cells.len() * 8);
+  for &(x, y) in cells {
+    for dy in -1..=1 {
+      for dx in -1..=1 {
+        if dx != 0 || dy != 0 {
+          *neighbors.entry((x + dx, y + dy)).or_default() += 1;
+        }
+      }
+    }
+  }
+  neighbors.into_iter().filter(|(c, n)| *n == 3 || (*n == 2 && cells.contains(c))).map(|(c, _)| c).collect()
+}
+
+/// Longest period of the population that `stabilize` recognizes, which covers the periods 1, 2, 3, 4, 5 and 15 of
+/// common objects, and their combinations.
+pub const MAX_STABLE_PERIOD: usize = 60;
+
+/// Runs the pattern until its population repeats itself for two of the longest periods, returning the cells and the
+/// generation that it stabilized at, or `None` if it has not stabilized after `max_generations`.
+pub fn stabilize(cells: &HashSet<Coord>, max_generations: usize) -> Option<(HashSet<Coord>, usize)> {
+  let window = 2 * MAX_STABLE_PERIOD;
+  let mut current = cells.clone();
+  let mut populations = vec![current.len()];
+  for generation in 1..=max_generations {
+    current = step(&current);
+    populations.push(current.len());
+    // Checking every few generations is enough, because a stable population stays stable.
+    if generation % 30 != 0 || populations.len() < window + MAX_STABLE_PERIOD {
+      continue;
+    }
+    let recent = populations.len() - window;
+    if (1..=MAX_STABLE_PERIOD).any(|p| (recent..populations.len()).all(|i| populations[i] == populations[i - p])) {
+      return Some((current, generation));
+    }
+  }
+  None
+}
+
+/// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if there are none.
+pub fn bounding_box<'a>(cells: impl IntoIterator<Item = &'a Coord>) -> Option<(isize, isize, isize, isize)> {
+  cells.into_iter().fold(None, |bbox, &(x, y)| {
+    Some(match bbox {
+      None => (x, y, x, y),
+      Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+    })
+  })
+}
+
+/// Translates the cells so that their bounding box starts at the origin.
+///
+/// Returns the translation that was removed along with the sorted cells, so that two patterns with the same shape
+/// compare equal regardless of where they are.
+pub fn normalize<'a>(cells: impl IntoIterator<Item = &'a Coord>) -> ((isize, isize), Vec<Coord>) {
+  let cells: Vec<Coord> = cells.into_iter().copied().collect();
+  let (x0, y0, _, _) = bounding_box(&cells).unwrap_or_default();
+  let mut normalized: Vec<Coord> = cells.into_iter().map(|(x, y)| (x - x0, y - y0)).collect();
+  normalized.sort_unstable();
+  ((x0, y0), normalized)
+}
+
+/// Runs the pattern for up to `max_period` generations looking for the first one that has the same shape as the
+/// start.
+pub fn find_period(cells: &HashSet<Coord>, max_period: usize) -> Option<Periodicity> {
+  if cells.is_empty() {
+    return None;
+  }
+  let (origin, shape) = normalize(cells);
+  let mut current = cells.clone();
+  for period in 1..=max_period {
+    current = step(&current);
+    if current.is_empty() {
+      return None;
+    }
+    if current.len() != shape.len() {
+      continue;
+    }
+    let (offset, next) = normalize(&current);
+    if next == shape {
+      return Some(Periodicity { period, displacement: (offset.0 - origin.0, offset.1 - origin.1) });
+    }
+  }
+  None
+}
@@ -4,11 +4,17 @@
 #![allow(clippy::needless_range_loop)]
 
 pub mod action;
+pub mod apgcode;
 pub mod app;
+pub mod catalog;
+pub mod census;
 pub mod cli;
 pub mod components;
 pub mod config;
+pub mod life;
 pub mod parsers;
+pub mod search;
+pub mod soup;
 pub mod tui;
 pub mod utils;
 
@@ -27,7 +33,29 @@ async fn tokio_main() -> Result<()> {
   initialize_panic_handler()?;
 
   let args = Cli::parse();
-  let mut app = App::new(args.tick_rate, args.frame_rate, args.pattern)?;
+  let pattern = args.load_pattern()?;
+  if let Some(command) = &args.command {
+    return command.run(pattern);
+  }
+  let mut app = App::new(args.tick_rate, args.frame_rate, pattern)?;
+  if args.render_mode.is_some() {
+    app.config.config.render_mode = args.render_mode;
+  }
+  if args.colors.is_some() {
+    app.config.config.colors = args.colors;
+  }
+  if args.seed.is_some() {
+    app.config.config.seed = args.seed;
+  }
+  if let Some(density) = args.density {
+    app.config.config.soup_density = density;
+  }
+  if args.soup_size.is_some() {
+    app.config.config.soup_size = args.soup_size;
+  }
+  if let Some(symmetry) = args.symmetry {
+    app.config.config.symmetry = symmetry;
+  }
   app.run().await?;
 
   Ok(())
@@ -2,19 +2,25 @@
 use std::{fs::File, io::Read};
 
 use color_eyre::eyre::Result;
+use serde::Serialize;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Cell {
   Dead(usize),
   Alive(usize),
 }
 
+impl Cell {
+  /// A dead cell that has not been alive for as long as anyone can remember.
+  pub const EMPTY: Cell = Cell::Dead(usize::MAX);
+}
+
 impl From<bool> for Cell {
   fn from(value: bool) -> Self {
     if value {
       Cell::Alive(0)
     } else {
-      Cell::Dead(0)
+      Cell::EMPTY
     }
   }
 }
@@ -61,7 +67,7 @@ impl FileType {
   }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 pub struct Pattern {
   pub cells: Vec<(isize, isize)>,
   pub name: Option<String>,
@@ -83,15 +89,98 @@ impl Pattern {
       return Err(color_eyre::eyre::eyre!("Could not read file to string: {}", e));
     }
 
-    let file_type: FileType = FileType::from_filename(filename).expect("Unrecognised file type.");
+    let file_type = match FileType::from_filename(filename) {
+      Some(t) => t,
+      None => return Err(color_eyre::eyre::eyre!("Unrecognised file type: {}", filename)),
+    };
 
     let pattern = match file_type {
-      FileType::Life => todo!("Not implemented"),
-      FileType::PlainText => todo!("Not implemented"),
+      FileType::Life => parse_life_file(&contents)?,
+      FileType::PlainText => parse_plaintext_file(&contents)?,
       FileType::RLE => parse_rle_file(&contents)?,
     };
     Ok(pattern)
   }
+
+  /// Parses a pattern in the RLE, Life or plaintext format, such as one pasted into the terminal.
+  pub fn parse(text: &str) -> Result<Pattern> {
+    let text = text.trim();
+    if text.starts_with("#Life") {
+      parse_life_file(text)
+    } else if text.lines().any(|line| line.trim_start().starts_with("x =")) || text.ends_with('!') {
+      parse_rle_file(text)
+    } else {
+      parse_plaintext_file(text)
+    }
+  }
+
+  /// Encodes the pattern in the RLE format, with lines of at most 70 characters.
+  pub fn to_rle(&self) -> String {
+    let (_, mut cells) = crate::life::normalize(&self.cells);
+    cells.sort_unstable_by_key(|&(x, y)| (y, x));
+    let (_, _, x1, y1) = crate::life::bounding_box(&cells).unwrap_or((0, 0, -1, -1));
+    // Runs of dead cells, live cells and line ends, where consecutive line ends mean empty rows.
+    let mut runs: Vec<(isize, char)> = vec![];
+    let mut push = |n: isize, tag: char| {
+      match runs.last_mut() {
+        _ if n == 0 => {},
+        Some((count, last)) if *last == tag => *count += n,
+        _ => runs.push((n, tag)),
+      }
+    };
+    let (mut x, mut y) = (0, 0);
+    for &(cx, cy) in &cells {
+      if cy > y {
+        push(cy - y, '$');
+        (x, y) = (0, cy);
+      }
+      push(cx - x, 'b');
+      push(1, 'o');
+      x = cx + 1;
+    }
+    push(1, '!');
+
+    let mut rle = String::new();
+    if let Some(name) = &self.name {
+      rle.push_str(&format!("#N {name}\n"));
+    }
+    rle.push_str(&format!("x = {}, y = {}, rule = B3/S23\n", x1 + 1, y1 + 1));
+    let mut line = String::new();
+    for (count, tag) in runs {
+      let run = if count == 1 { tag.to_string() } else { format!("{count}{tag}") };
+      if line.len() + run.len() > 70 {
+        rle.push_str(&line);
+        rle.push('\n');
+        line.clear();
+      }
+      line.push_str(&run);
+    }
+    rle.push_str(&line);
+    rle.push('\n');
+    rle
+  }
+
+  /// Loads a pattern from a file, or by the name of a built-in pattern, or from an apgcode.
+  pub fn load(spec: &str) -> Result<Pattern> {
+    let exists = std::path::Path::new(spec).exists();
+    if !exists && crate::apgcode::is_apgcode(spec) {
+      Pattern::from_apgcode(spec)
+    } else if !exists && crate::catalog::names().any(|name| name.eq_ignore_ascii_case(spec)) {
+      crate::catalog::get(spec)
+    } else {
+      Pattern::from_file(spec)
+    }
+  }
+
+  /// Decodes an apgcode such as `xq4_153` into a pattern.
+  pub fn from_apgcode(code: &str) -> Result<Pattern> {
+    crate::apgcode::decode(code)
+  }
+
+  /// Returns the canonical apgcode of a still life, oscillator or spaceship.
+  pub fn apgcode(&self) -> Result<String> {
+    crate::apgcode::encode(self)
+  }
 }
 
 pub fn parse_rle_file(s: &str) -> Result<Pattern> {
@@ -223,3 +312,152 @@ pub fn parse_rle_file(s: &str) -> Result<Pattern> {
 
   Ok(pattern)
 }
+
+/// Parses the plaintext `.cells` format, where `!` starts a comment, `.` is a dead cell and `O` is a live cell.
+pub fn parse_plaintext_file(s: &str) -> Result<Pattern> {
+  let mut pattern: Pattern = Default::default();
+
+  for line in s.lines().take_while(|x| x.starts_with('!')) {
+    let line = line[1..].trim();
+    if let Some(name) = line.strip_prefix("Name:") {
+      pattern.name = Some(name.trim().to_string());
+    } else if let Some(author) = line.strip_prefix("Author:") {
+      pattern.author = Some(author.trim().to_string());
+    } else if !line.is_empty() {
+      if let Some(d) = pattern.description {
+        pattern.description = Some(format!("{}\n{}", d, line));
+      } else {
+        pattern.description = Some(String::from(line));
+      }
+    }
+  }
+
+  let (mut width, mut height) = (0, 0);
+  for (y, line) in s.lines().skip_while(|x| x.starts_with('!')).enumerate() {
+    for (x, c) in line.trim_end().chars().enumerate() {
+      match c {
+        '.' => {},
+        'O' | 'o' | '*' => pattern.cells.push((x as isize, y as isize)),
+        unknown => {
+          return Err(color_eyre::eyre::eyre!(
+            "Unexpected character `{}` while reading data from a `.cells` file.",
+            unknown
+          ))
+        },
+      }
+      width = width.max(x + 1);
+    }
+    height = y + 1;
+  }
+  pattern.area = Some((width, height));
+
+  Ok(pattern)
+}
+
+/// Parses the Life 1.05 and Life 1.06 formats.
+///
+/// Life 1.06 lists the coordinates of every live cell, one per line. Life 1.05 contains blocks of cells in the
+/// plaintext style, each starting with a `#P x y` line giving the position of its top left corner.
+pub fn parse_life_file(s: &str) -> Result<Pattern> {
+  let mut pattern: Pattern = Default::default();
+  let mut lines = s.lines();
+
+  match lines.next().map(str::trim) {
+    Some("#Life 1.06") => {
+      for line in lines.filter(|x| !x.starts_with('#') && !x.trim().is_empty()) {
+        let mut coords = line.split_whitespace().map(str::parse::<isize>);
+        match (coords.next(), coords.next()) {
+          (Some(Ok(x)), Some(Ok(y))) => pattern.cells.push((x, y)),
+          _ => return Err(color_eyre::eyre::eyre!("Invalid coordinates `{}` in a Life 1.06 file.", line)),
+        }
+      }
+    },
+    Some("#Life 1.05") => {
+      let (mut x0, mut y) = (0, 0);
+      for line in lines {
+        let mut linedata = line.chars().skip(1);
+        if line.starts_with('#') {
+          match linedata.next() {
+            Some('D') | Some('C') => {
+              let description: String = linedata.collect();
+              let description = description.trim();
+              if let Some(d) = pattern.description {
+                pattern.description = Some(format!("{}\n{}", d, description));
+              } else {
+                pattern.description = Some(String::from(description));
+              }
+            },
+            Some('P') => {
+              let position: String = linedata.collect();
+              let mut coords = position.split_whitespace().map(str::parse::<isize>);
+              match (coords.next(), coords.next()) {
+                (Some(Ok(x)), Some(Ok(py))) => (x0, y) = (x, py),
+                _ => return Err(color_eyre::eyre::eyre!("Invalid block position `{}` in a Life 1.05 file.", line)),
+              }
+            },
+            _ => {},
+          }
+          continue;
+        }
+        for (x, c) in line.trim_end().chars().enumerate() {
+          match c {
+            '.' => {},
+            '*' => pattern.cells.push((x0 + x as isize, y)),
+            unknown => {
+              return Err(color_eyre::eyre::eyre!(
+                "Unexpected character `{}` while reading data from a Life 1.05 file.",
+                unknown
+              ))
+            },
+          }
+        }
+        y += 1;
+      }
+    },
+    _ => return Err(color_eyre::eyre::eyre!("Missing `#Life 1.05` or `#Life 1.06` header in a `.lif` file.")),
+  }
+
+  if let Some((x0, y0, x1, y1)) = crate::life::bounding_box(&pattern.cells) {
+    pattern.area = Some(((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize));
+  }
+
+  Ok(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_plaintext() -> Result<()> {
+    let pattern = parse_plaintext_file("!Name: Glider\n!The first spaceship.\n.O.\n..O\nOOO\n")?;
+    assert_eq!(pattern.name.as_deref(), Some("Glider"));
+    assert_eq!(pattern.description.as_deref(), Some("The first spaceship."));
+    assert_eq!(pattern.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert_eq!(pattern.area, Some((3, 3)));
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_life() -> Result<()> {
+    let life_106 = parse_life_file("#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n")?;
+    let life_105 = parse_life_file("#Life 1.05\n#D Glider\n#P 0 0\n.*.\n..*\n***\n")?;
+    assert_eq!(life_106.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert_eq!(life_105.cells, life_106.cells);
+    assert!(parse_life_file("1 0\n").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_rle_round_trip() -> Result<()> {
+    let glider = Pattern::parse("x = 3, y = 3\nbo$2bo$3o!")?;
+    assert_eq!(glider.to_rle(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    let gap = Pattern { cells: vec![(0, 0), (5, 3)], ..Pattern::default() };
+    assert_eq!(gap.to_rle(), "x = 6, y = 4, rule = B3/S23\no3$5bo!\n");
+    assert_eq!(Pattern::parse(&gap.to_rle())?.cells, gap.cells);
+    assert_eq!(Pattern::parse(".O\nO.\n")?.cells, vec![(1, 0), (0, 1)]);
+    Ok(())
+  }
+}
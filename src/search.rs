@@ -0,0 +1,161 @@
+// Headless searches of random soups for the objects that they produce, in the style of apgsearch.
+use std::{
+  collections::HashSet,
+  sync::atomic::{AtomicUsize, Ordering},
+  thread,
+};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+  census::{Census, Object},
+  life::{self, Coord},
+  soup::{self, Symmetry},
+};
+
+/// Number of generations after which a soup that has not stabilized is given up on.
+pub const MAX_GENERATIONS: usize = 20_000;
+
+#[derive(Debug, Clone)]
+pub struct Search {
+  pub soups: usize,
+  /// Seed of the first soup, which the seeds of the other soups count up from.
+  pub seed: u64,
+  pub threads: usize,
+  /// How many cells apart the cells of one object can be in the census of each soup.
+  pub distance: usize,
+  /// Objects that are found in at most this many soups are rare.
+  pub rare: usize,
+  pub symmetry: Symmetry,
+}
+
+/// An object and the soups it was found in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tally {
+  #[serde(flatten)]
+  pub object: Object,
+  pub soups: usize,
+  /// Seeds of the soups the object was found in, the lowest first, up to the number of soups that makes it rare.
+  pub seeds: Vec<u64>,
+  pub rare: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Results {
+  pub soups: usize,
+  /// Seeds of the soups that did not stabilize within `MAX_GENERATIONS`.
+  pub unstabilized: Vec<u64>,
+  /// Objects from the most to the least common.
+  pub objects: Vec<Tally>,
+}
+
+impl Search {
+  pub fn seed(&self, index: usize) -> u64 {
+    self.seed.wrapping_add(index as u64)
+  }
+
+  /// Searches every soup, sharing them out between the threads. The results only depend on the seeds, not on which
+  /// thread searched which soup.
+  pub fn run(&self) -> Results {
+    let next = AtomicUsize::new(0);
+    let partials: Vec<Results> = thread::scope(|scope| {
+      let workers: Vec<_> = (0..self.threads.max(1))
+        .map(|_| {
+          scope.spawn(|| {
+            let mut results = Results::default();
+            loop {
+              let index = next.fetch_add(1, Ordering::Relaxed);
+              if index >= self.soups {
+                return results;
+              }
+              self.search(self.seed(index), &mut results);
+            }
+          })
+        })
+        .collect();
+      workers.into_iter().map(|worker| worker.join().expect("Search thread panicked")).collect()
+    });
+    let mut results = Results::default();
+    for partial in partials {
+      results.merge(partial, self.rare);
+    }
+    results.finish(self.rare)
+  }
+
+  /// Runs the soup with the given seed until it stabilizes and counts the objects it produced.
+  fn search(&self, seed: u64, results: &mut Results) {
+    results.soups += 1;
+    let cells: HashSet<Coord> = soup::seeded(seed, soup::SIZE, self.symmetry).into_iter().collect();
+    let Some((cells, _)) = life::stabilize(&cells, MAX_GENERATIONS) else {
+      results.unstabilized.push(seed);
+      return;
+    };
+    let cells: Vec<Coord> = cells.into_iter().sorted().collect();
+    for object in Census::take(&cells, self.distance, None).objects {
+      results.add(Tally { object, soups: 1, seeds: vec![seed], rare: false }, self.rare);
+    }
+  }
+}
+
+impl Results {
+  fn add(&mut self, tally: Tally, max_seeds: usize) {
+    match self.objects.iter_mut().find(|t| t.object.is_same(&tally.object)) {
+      Some(t) => {
+        t.object.count += tally.object.count;
+        t.soups += tally.soups;
+        t.seeds.extend(tally.seeds);
+        t.seeds.sort_unstable();
+        t.seeds.truncate(max_seeds);
+      },
+      None => self.objects.push(Tally { seeds: tally.seeds.into_iter().take(max_seeds).collect(), ..tally }),
+    }
+  }
+
+  fn merge(&mut self, other: Results, max_seeds: usize) {
+    self.soups += other.soups;
+    self.unstabilized.extend(other.unstabilized);
+    for tally in other.objects {
+      self.add(tally, max_seeds);
+    }
+  }
+
+  /// Flags the rare objects and sorts everything, so that the results do not depend on the order of the soups.
+  fn finish(mut self, rare: usize) -> Self {
+    self.unstabilized.sort_unstable();
+    for tally in &mut self.objects {
+      tally.rare = tally.soups <= rare;
+    }
+    self.objects.sort_by(|a, b| {
+      (b.object.count, a.object.kind, &a.object.apgcode, a.object.population).cmp(&(
+        a.object.count,
+        b.object.kind,
+        &b.object.apgcode,
+        b.object.population,
+      ))
+    });
+    self
+  }
+
+  /// Lists the rare objects with the seeds of the soups they were found in.
+  pub fn rare(&self) -> impl Iterator<Item = &Tally> {
+    self.objects.iter().filter(|tally| tally.rare)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_deterministic() {
+    let search = |threads| Search { soups: 4, seed: 42, threads, distance: 2, rare: 1, symmetry: Symmetry::C1 }.run();
+    let results = search(1);
+    assert_eq!(results.soups, 4);
+    assert_eq!(results, search(3));
+    assert_eq!(results.objects[0].object.name.as_deref(), Some("block"));
+    assert!(results.rare().all(|tally| tally.soups == tally.seeds.len()));
+  }
+}
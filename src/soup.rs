@@ -0,0 +1,118 @@
+// Random starting patterns, for the universe and for soup searches.
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::life::Coord;
+
+/// Fraction of cells that are alive in a soup, as in apgsearch.
+pub const DENSITY: f64 = 0.5;
+
+/// Width and height of the soups that are searched, as in apgsearch.
+pub const SIZE: usize = 16;
+
+/// Symmetries of soups, named like the ones of apgsearch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum Symmetry {
+  /// No symmetry.
+  #[default]
+  C1,
+  /// Unchanged when rotated by 180 degrees.
+  C2,
+  /// Unchanged when rotated by 90 degrees, in a square.
+  C4,
+  /// Mirrored left to right.
+  D2,
+  /// Mirrored left to right and top to bottom.
+  D4,
+  /// Unchanged when rotated by 90 degrees or mirrored in any axis or diagonal, in a square.
+  D8,
+}
+
+impl Symmetry {
+  /// Returns the cells that `(x, y)` is mapped to in a `width` by `height` soup, including itself.
+  fn images(&self, (x, y): (usize, usize), width: usize, height: usize) -> Vec<(usize, usize)> {
+    let (mx, my) = (width - 1 - x, height - 1 - y);
+    match self {
+      Symmetry::C1 => vec![(x, y)],
+      Symmetry::C2 => vec![(x, y), (mx, my)],
+      Symmetry::C4 => vec![(x, y), (my, x), (mx, my), (y, mx)],
+      Symmetry::D2 => vec![(x, y), (mx, y)],
+      Symmetry::D4 => vec![(x, y), (mx, y), (x, my), (mx, my)],
+      Symmetry::D8 => vec![(x, y), (my, x), (mx, my), (y, mx), (y, x), (mx, y), (x, my), (my, mx)],
+    }
+  }
+
+  /// Returns the size that a soup of `width` by `height` has with this symmetry, which is a square for the ones that
+  /// rotate by 90 degrees.
+  pub fn size(&self, width: usize, height: usize) -> (usize, usize) {
+    match self {
+      Symmetry::C4 | Symmetry::D8 => (width.min(height), width.min(height)),
+      _ => (width, height),
+    }
+  }
+}
+
+/// Returns the live cells of a soup of at most `width` by `height` as `(x, y)`. Each cell is drawn from `rng` in turn,
+/// row by row, except the ones that the symmetry copies from a cell before them.
+pub fn random(
+  rng: &mut impl Rng,
+  width: usize,
+  height: usize,
+  density: f64,
+  symmetry: Symmetry,
+) -> Vec<(usize, usize)> {
+  let (width, height) = symmetry.size(width, height);
+  let mut drawn: HashMap<(usize, usize), bool> = HashMap::new();
+  let mut cells = vec![];
+  for y in 0..height {
+    for x in 0..width {
+      let first = symmetry.images((x, y), width, height).into_iter().min_by_key(|&(x, y)| (y, x)).unwrap_or((x, y));
+      let alive = match drawn.get(&first) {
+        Some(&alive) => alive,
+        None => *drawn.entry((x, y)).or_insert(rng.gen_bool(density)),
+      };
+      if alive {
+        cells.push((x, y));
+      }
+    }
+  }
+  cells
+}
+
+/// Returns the square soup that a seed generates, which is the same on every run and every machine.
+pub fn seeded(seed: u64, size: usize, symmetry: Symmetry) -> Vec<Coord> {
+  let mut rng = StdRng::seed_from_u64(seed);
+  random(&mut rng, size, size, DENSITY, symmetry).into_iter().map(|(x, y)| (x as isize, y as isize)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_seeded() {
+    assert_eq!(seeded(7, SIZE, Symmetry::C1), seeded(7, SIZE, Symmetry::C1));
+    assert_ne!(seeded(7, SIZE, Symmetry::C1), seeded(8, SIZE, Symmetry::C1));
+    assert!(seeded(7, SIZE, Symmetry::C1).iter().all(|&(x, y)| (0..16).contains(&x) && (0..16).contains(&y)));
+  }
+
+  #[test]
+  fn test_symmetries() {
+    for symmetry in Symmetry::value_variants() {
+      let cells: HashSet<(usize, usize)> =
+        random(&mut StdRng::seed_from_u64(1), 6, 5, 0.5, *symmetry).into_iter().collect();
+      let (width, height) = symmetry.size(6, 5);
+      for &cell in &cells {
+        assert!(symmetry.images(cell, width, height).iter().all(|image| cells.contains(image)), "{symmetry:?}");
+      }
+    }
+    assert_eq!(Symmetry::D8.size(6, 5), (5, 5));
+  }
+}
@@ -1,12 +1,17 @@
 use std::{
+  io::Write,
   ops::{Deref, DerefMut},
   time::Duration,
 };
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use color_eyre::eyre::Result;
 use crossterm::{
   cursor,
-  event::{DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent},
+  event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event as CrosstermEvent,
+    KeyEvent, KeyEventKind, MouseEvent,
+  },
   terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
@@ -24,6 +29,14 @@ pub fn io() -> IO {
   std::io::stdout()
 }
 
+/// Puts text on the system clipboard with the OSC 52 escape sequence, which also works over SSH.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+  let mut io = io();
+  write!(io, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+  io.flush()?;
+  Ok(())
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
   Init,
@@ -157,7 +170,7 @@ impl Tui {
 
   pub fn enter(&mut self) -> Result<()> {
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(io(), EnterAlternateScreen, cursor::Hide)?;
+    crossterm::execute!(io(), EnterAlternateScreen, EnableBracketedPaste, cursor::Hide)?;
     if self.mouse {
       crossterm::execute!(io(), EnableMouseCapture)?;
     }
@@ -172,7 +185,7 @@ impl Tui {
       if self.mouse {
         crossterm::execute!(io(), DisableMouseCapture)?;
       }
-      crossterm::execute!(io(), LeaveAlternateScreen, cursor::Show)?;
+      crossterm::execute!(io(), DisableBracketedPaste, LeaveAlternateScreen, cursor::Show)?;
       crossterm::terminal::disable_raw_mode()?;
     }
     Ok(())